@@ -0,0 +1,5 @@
+mod r#struct;
+
+/// needs: Copy
+pub type ItemID = usize;
+pub use r#struct::ChartModel;
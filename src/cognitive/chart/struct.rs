@@ -0,0 +1,412 @@
+use super::super::CognitiveModel;
+use super::super::error::Result;
+use super::super::lambda::{LambdaModel, Node};
+use super::ItemID;
+use crate::lexicon::LexiconEntry;
+use crate::syntax::{FeatureSet, SyntaxValue, Variable};
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::rc::Rc;
+
+/// an expectation on top of the stack below it
+#[derive(Debug)]
+struct Item<K> {
+    node: Node<K>,
+    below: Option<ItemID>,
+    /// the variables of the expectation, which binding those above may change
+    variables: BTreeSet<Variable>,
+    /// the smallest variable not in the stack up to this item
+    next_variable: Variable,
+    /// the branches and the items that stand on this item
+    refs: usize,
+}
+
+/// every expectation stack of every live branch, with common bottoms stored once
+#[derive(Debug)]
+struct Chart<K> {
+    items: Vec<Option<Item<K>>>,
+    /// the slots of dropped items, to be reused
+    free: Vec<ItemID>,
+    index: HashMap<(Option<ItemID>, Node<K>), ItemID>,
+}
+
+impl<K: Clone + Ord + Hash> Chart<K> {
+    fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            free: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    fn item(&self, id: ItemID) -> &Item<K> {
+        self.items[id]
+            .as_ref()
+            .expect("an item stood on is never dropped")
+    }
+
+    /// the same expectation on the same stack is the same item; the caller stands on it
+    fn intern(&mut self, below: Option<ItemID>, node: Node<K>) -> ItemID {
+        if let Some(&id) = self.index.get(&(below, node.clone())) {
+            self.retain(Some(id));
+            return id;
+        }
+        self.retain(below);
+        let item = Item {
+            variables: node.variables(),
+            next_variable: node.next_variable().max(self.next_variable(below)),
+            node: node.clone(),
+            below,
+            refs: 1,
+        };
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.items[id] = Some(item);
+                id
+            }
+            None => {
+                self.items.push(Some(item));
+                self.items.len() - 1
+            }
+        };
+        self.index.insert((below, node), id);
+        id
+    }
+
+    fn retain(&mut self, id: Option<ItemID>) {
+        if let Some(id) = id {
+            self.items[id]
+                .as_mut()
+                .expect("an item stood on is never dropped")
+                .refs += 1;
+        }
+    }
+
+    /// drops the items nothing stands on anymore, down the stack
+    fn release(&mut self, mut id: Option<ItemID>) {
+        while let Some(cur) = id {
+            let item = self.items[cur]
+                .as_mut()
+                .expect("an item stood on is never dropped");
+            item.refs -= 1;
+            if item.refs > 0 {
+                return;
+            }
+            let Some(item) = self.items[cur].take() else {
+                unreachable!("Already checked that the item is there")
+            };
+            self.index.remove(&(item.below, item.node));
+            self.free.push(cur);
+            id = item.below;
+        }
+    }
+
+    fn next_variable(&self, top: Option<ItemID>) -> Variable {
+        top.map_or(0, |id| self.item(id).next_variable)
+    }
+
+    /// item IDs from the bottom of the stack up to `top`
+    fn stack(&self, top: Option<ItemID>) -> Vec<ItemID> {
+        let mut stack = Vec::new();
+        let mut cur = top;
+        while let Some(id) = cur {
+            stack.push(id);
+            cur = self.item(id).below;
+        }
+        stack.reverse();
+        stack
+    }
+
+    /// item IDs from the bottom up to `top` of the part of the stack that a step may look at:
+    /// the top, a projection onto the lambda below it, and that lambda, then the projections
+    /// that land one after another below them, then down to the deepest item sharing
+    /// a variable with those, as binding it changes that item
+    fn window(&self, top: Option<ItemID>) -> Vec<ItemID> {
+        let is_projection = |id: ItemID| matches!(self.item(id).node, Node::Projection { .. });
+        let mut window: Vec<ItemID> = Vec::new();
+        let mut cur = top;
+        while let Some(id) = cur {
+            let lands = window.last().is_some_and(|&above| is_projection(above));
+            if window.len() >= 3 && !lands && !is_projection(id) {
+                break;
+            }
+            window.push(id);
+            cur = self.item(id).below;
+        }
+
+        let variables = window.iter().flat_map(|&id| &self.item(id).variables);
+        let variables = variables.copied().collect::<BTreeSet<_>>();
+        let mut below = Vec::new();
+        while let Some(id) = cur {
+            below.push(id);
+            if !self.item(id).variables.is_disjoint(&variables) {
+                window.append(&mut below);
+            }
+            cur = self.item(id).below;
+        }
+        window.reverse();
+        window
+    }
+
+    /// the expectations of the top three items, from the bottom
+    fn peek(&self, top: Option<ItemID>) -> Vec<Node<K>> {
+        let mut nodes = Vec::new();
+        let mut cur = top;
+        while let Some(id) = cur
+            && nodes.len() < 3
+        {
+            nodes.push(self.item(id).node.clone());
+            cur = self.item(id).below;
+        }
+        nodes.reverse();
+        nodes
+    }
+}
+
+/// `LambdaModel` whose branches share one chart of expectations,
+/// so that cloning a branch only copies its top
+pub struct ChartModel<K: Clone + Ord + Hash> {
+    chart: Rc<RefCell<Chart<K>>>,
+    top: Option<ItemID>,
+    /// `LambdaModel` of the top expectation and the lambda below it only,
+    /// for the queries that only look at them
+    peek: LambdaModel<K>,
+}
+
+impl<K: Clone + Ord + Hash> ChartModel<K> {
+    fn to_lambda_model(&self) -> LambdaModel<K> {
+        let chart = self.chart.borrow();
+        let stack = chart.stack(self.top).into_iter();
+        let expects = stack.map(|id| chart.item(id).node.clone()).collect();
+        LambdaModel::from_expects(expects, self.peek.get_corner())
+    }
+
+    /// runs `step` on the part of the stack it may look at, then stores what changed on top
+    /// of what did not; `step` is given the offset that renames variables apart from the rest
+    fn apply(
+        &mut self,
+        step: impl FnOnce(&mut LambdaModel<K>, Variable) -> Result<()>,
+    ) -> Result<()> {
+        let mut chart = self.chart.borrow_mut();
+        let window = chart.window(self.top);
+        let below = window.first().and_then(|&id| chart.item(id).below);
+        let expects = window.iter().map(|&id| chart.item(id).node.clone());
+        let mut model = LambdaModel::from_expects(expects.collect(), self.peek.get_corner());
+        // the model knows the variables of the window only, and those below are skipped too
+        let window_next = window.iter().map(|&id| chart.item(id).node.next_variable());
+        let window_next = window_next.max().unwrap_or(0);
+        step(
+            &mut model,
+            chart.next_variable(below).saturating_sub(window_next),
+        )?;
+        let corner = model.get_corner();
+        let expects = model.into_expects();
+
+        let shared = window
+            .iter()
+            .zip(&expects)
+            .take_while(|&(&id, node)| &chart.item(id).node == node)
+            .count();
+        let mut top = shared.checked_sub(1).map(|i| window[i]).or(below);
+        chart.retain(top);
+        for node in expects.into_iter().skip(shared) {
+            let above = chart.intern(top, node);
+            chart.release(top);
+            top = Some(above);
+        }
+        chart.release(self.top);
+
+        self.top = top;
+        self.peek = LambdaModel::from_expects(chart.peek(top), corner);
+        Ok(())
+    }
+}
+
+impl<K: Clone + Ord + Hash> Clone for ChartModel<K> {
+    fn clone(&self) -> Self {
+        self.chart.borrow_mut().retain(self.top);
+        Self {
+            chart: Rc::clone(&self.chart),
+            top: self.top,
+            peek: self.peek.clone(),
+        }
+    }
+}
+
+impl<K: Clone + Ord + Hash> Drop for ChartModel<K> {
+    fn drop(&mut self) {
+        self.chart.borrow_mut().release(self.top);
+    }
+}
+
+impl<K: Clone + Ord + Hash> CognitiveModel<K> for ChartModel<K> {
+    fn init(target: FeatureSet<K>) -> Self {
+        let mut chart = Chart::new();
+        let top = chart.intern(None, Node::from(target.clone()));
+        Self {
+            chart: Rc::new(RefCell::new(chart)),
+            top: Some(top),
+            peek: LambdaModel::init(target),
+        }
+    }
+    fn understood(&self) -> bool {
+        self.top.is_none()
+    }
+    fn demand(&self) -> bool {
+        self.peek.demand()
+    }
+    fn receive(&mut self, token: K) -> Result<()> {
+        self.apply(|model, _| model.receive(token))
+    }
+    fn wonder(&self) -> Option<&SyntaxValue<K>> {
+        self.peek.wonder()
+    }
+    fn moved(&self) -> bool {
+        self.peek.moved()
+    }
+    fn decide(&mut self, mut entry: LexiconEntry<K>) -> Result<()> {
+        self.apply(|model, offset| {
+            entry.offset_variables(offset);
+            model.decide(entry)
+        })
+    }
+    fn pending(&self) -> usize {
+        self.peek.pending()
+    }
+    fn reorder(&mut self, index: usize) -> Result<()> {
+        self.apply(|model, _| model.reorder(index))
+    }
+}
+
+impl<K: Display + Clone + Ord + Hash> Display for ChartModel<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_lambda_model())
+    }
+}
+
+impl<K: Debug + Clone + Ord + Hash> Debug for ChartModel<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChartModel")
+            .field("top", &self.top)
+            .field("expects", &self.to_lambda_model().into_expects())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::English;
+    use crate::interner::GlobalKey;
+    use crate::interpreter::interpret;
+    use std::cell::Cell;
+
+    #[test]
+    fn branches_share_chart() {
+        let model = ChartModel::init(FeatureSet::from_category("Sentence"));
+        let mut first = model.clone();
+        let mut second = model.clone();
+        first.receive("the").unwrap();
+        second.receive("the").unwrap();
+        assert_eq!(first.top, second.top);
+        assert_eq!(model.chart.borrow().items.len(), 2);
+        assert_eq!(format!("{first}"), "λ(the -> Sentence)");
+    }
+
+    #[test]
+    fn dropped_branches_leave_chart() {
+        let model = ChartModel::init(FeatureSet::from_category("Sentence"));
+        let mut branch = model.clone();
+        branch.receive("the").unwrap();
+        assert_eq!(model.chart.borrow().index.len(), 2);
+        drop(branch);
+        // only the stack of `model` is left, and the slot of the dropped item is reused
+        assert_eq!(model.chart.borrow().index.len(), 1);
+        let mut branch = model.clone();
+        branch.receive("a").unwrap();
+        assert_eq!(model.chart.borrow().items.len(), 2);
+    }
+
+    #[test]
+    fn chart_interprets_like_lambda() {
+        let dialect = English::init();
+        let sentence = "the child ate an apple in the room.";
+        let chart = interpret::<_, ChartModel<_>>(&dialect, sentence, "Sentence").unwrap();
+        let lambda = interpret::<_, LambdaModel<_>>(&dialect, sentence, "Sentence").unwrap();
+        // lexicon entries come in no particular order
+        assert_eq!(chart.len(), lambda.len());
+        assert!(chart.iter().all(|actions| lambda.contains(actions)));
+    }
+
+    thread_local! {
+        /// the steps taken, and the most items the chart held after one
+        static STEPS: Cell<usize> = const { Cell::new(0) };
+        static MOST: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// a chart model watching the size of its chart
+    #[derive(Clone)]
+    struct Watched(ChartModel<GlobalKey>);
+
+    impl Watched {
+        fn watch(&self, result: Result<()>) -> Result<()> {
+            let live = self.0.chart.borrow().index.len();
+            STEPS.with(|steps| steps.set(steps.get() + 1));
+            MOST.with(|most| most.set(most.get().max(live)));
+            result
+        }
+    }
+
+    impl Display for Watched {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            Display::fmt(&self.0, f)
+        }
+    }
+
+    impl CognitiveModel<GlobalKey> for Watched {
+        fn init(target: FeatureSet<GlobalKey>) -> Self {
+            Watched(ChartModel::init(target))
+        }
+        fn understood(&self) -> bool {
+            self.0.understood()
+        }
+        fn demand(&self) -> bool {
+            self.0.demand()
+        }
+        fn receive(&mut self, token: GlobalKey) -> Result<()> {
+            let result = self.0.receive(token);
+            self.watch(result)
+        }
+        fn wonder(&self) -> Option<&SyntaxValue<GlobalKey>> {
+            self.0.wonder()
+        }
+        fn moved(&self) -> bool {
+            self.0.moved()
+        }
+        fn decide(&mut self, entry: LexiconEntry<GlobalKey>) -> Result<()> {
+            let result = self.0.decide(entry);
+            self.watch(result)
+        }
+        fn pending(&self) -> usize {
+            self.0.pending()
+        }
+        fn reorder(&mut self, index: usize) -> Result<()> {
+            let result = self.0.reorder(index);
+            self.watch(result)
+        }
+    }
+
+    #[test]
+    fn chart_shrinks_as_branches_die() {
+        let dialect = English::init();
+        let sentence = "the child ate an apple in the room.";
+        let result = interpret::<_, Watched>(&dialect, sentence, "Sentence").unwrap();
+        assert_eq!(result.len(), 2);
+        // tens of thousands of steps, but the items of a dead branch are dropped with it
+        let (steps, most) = (STEPS.with(Cell::get), MOST.with(Cell::get));
+        assert!(steps > 10_000);
+        assert!(most <= 100, "{most} items at once");
+    }
+}
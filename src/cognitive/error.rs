@@ -6,6 +6,10 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub enum Error {
     LambdaModel(#[from] super::lambda::Error),
     TreeModel(#[from] super::tree::Error),
+
+    StdIO(#[from] std::io::Error),
+
+    GraphvizDotNotInstalled,
 }
 
 impl std::error::Error for Error {}
@@ -0,0 +1,56 @@
+use super::error::{Error, Result};
+use super::style::RenderStyle;
+use super::{LambdaModel, TreeModel};
+use graphviz_rust::cmd::{CommandArg, Format};
+use graphviz_rust::exec_dot;
+use std::fmt::Display;
+use std::process::Command;
+
+pub fn is_dot_installed() -> bool {
+    match Command::new("dot").arg("-V").output() {
+        Ok(output) => output.status.success(),
+        Err(_) => false,
+    }
+}
+
+/// both cognitive models after following the same action prefix
+pub struct Snapshot<'a, K> {
+    lambda: &'a LambdaModel<K>,
+    tree: &'a TreeModel<K>,
+}
+
+impl<'a, K> Snapshot<'a, K> {
+    pub fn new(lambda: &'a LambdaModel<K>, tree: &'a TreeModel<K>) -> Self {
+        Self { lambda, tree }
+    }
+}
+
+impl<K: Display + Clone + Ord> Snapshot<'_, K> {
+    /// the expectation stack and the partial tree side by side
+    pub fn to_dot_graph(&self, style: &RenderStyle<K>) -> Result<String> {
+        let mut graph = style.to_dot_header();
+
+        graph.push_str("subgraph cluster_lambda {\n");
+        graph.push_str("    label=\"LambdaModel\";\n");
+        self.lambda.write_dot_nodes(&mut graph, "lambda_", style)?;
+        graph.push_str("}\n");
+
+        graph.push_str("subgraph cluster_tree {\n");
+        graph.push_str("    label=\"TreeModel\";\n");
+        self.tree.write_dot_nodes(&mut graph, "tree_", style)?;
+        graph.push_str("}\n");
+
+        graph.push_str("}\n");
+        Ok(graph)
+    }
+
+    pub fn to_png(&self, filename: String, style: &RenderStyle<K>) -> Result<()> {
+        if !is_dot_installed() {
+            return Err(Error::GraphvizDotNotInstalled);
+        }
+        let dot = self.to_dot_graph(style)?;
+
+        exec_dot(dot, vec![Format::Png.into(), CommandArg::Output(filename)])?;
+        Ok(())
+    }
+}
@@ -9,8 +9,21 @@ pub enum Error {
     #[from]
     Syntax(crate::syntax::Error),
     QueryAndEntryTypeMismatch,
+    KeyDoesNotUnify,
     LambdaToMustBeFeatures,
+    ProjectOntoLambda,
     TypeConversion,
+    MovedCopyInconsistent,
+    #[from(ignore)]
+    CannotReorder(usize),
+    Overtaken,
+
+    #[from]
+    StdFmt(std::fmt::Error),
+    #[from]
+    StdIO(std::io::Error),
+
+    GraphvizDotNotInstalled,
 }
 
 impl std::error::Error for Error {}
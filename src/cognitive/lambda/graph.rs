@@ -0,0 +1,135 @@
+use super::LambdaModel;
+use super::error::{Error, Result};
+use super::node::{Argument, Node};
+use crate::cognitive::graph::is_dot_installed;
+use crate::cognitive::style::RenderStyle;
+use graphviz_rust::cmd::{CommandArg, Format};
+use graphviz_rust::exec_dot;
+use std::fmt::Display;
+use std::fmt::Write;
+
+impl<K: Display + Ord> Node<K> {
+    /// writes this node and its nested `to` chain, left to right
+    fn write_dot(&self, graph: &mut String, id: &str, style: &RenderStyle<K>) -> Result<()> {
+        match self {
+            Node::Value { value } => {
+                writeln!(graph, r#"    "{}" [{}];"#, id, value.to_dot_attr(style))?;
+            }
+            Node::Lambda { from, to } => {
+                let to_id = format!("{id}.");
+                to.write_dot(graph, &to_id, style)?;
+                write_dot_arguments(graph, id, from, &to_id, style)?;
+            }
+            Node::From { from } => {
+                // nothing to attach to yet
+                let to_id = format!("{id}.");
+                writeln!(graph, r#"    "{}" [shape=point];"#, to_id)?;
+                write_dot_arguments(graph, id, from, &to_id, style)?;
+            }
+            Node::Projection { ignore } => {
+                writeln!(
+                    graph,
+                    r#"    "{}" [label=">>\nignore:\n{}", shape=diamond, color=orange];"#,
+                    id,
+                    ignore.to_dot_label(style)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// arguments are chained as "{id}", "{id}.1", ..., then `to_id`
+fn write_dot_arguments<K: Display + Ord>(
+    graph: &mut String,
+    id: &str,
+    from: &[Argument<K>],
+    to_id: &str,
+    style: &RenderStyle<K>,
+) -> Result<()> {
+    let mut ids = (0..from.len())
+        .map(|i| match i {
+            0 => id.to_string(),
+            _ => format!("{id}.{i}"),
+        })
+        .collect::<Vec<_>>();
+    for (arg_id, arg) in ids.iter().zip(from) {
+        // a moved copy is drawn like the traces of `TreeModel`
+        let dashed = match arg.moved {
+            true => ", style=dashed",
+            false => "",
+        };
+        let attr = arg.value.to_dot_attr(style);
+        writeln!(graph, r#"    "{}" [{}{}];"#, arg_id, attr, dashed)?;
+    }
+    ids.push(to_id.to_string());
+    for pair in ids.windows(2) {
+        writeln!(graph, r#"    "{}" -> "{}" [label="λ"];"#, pair[0], pair[1])?;
+    }
+    let same = ids.iter().map(|id| format!(r#""{id}";"#));
+    writeln!(
+        graph,
+        "    {{ rank=same; {} }}",
+        same.collect::<Vec<_>>().join(" ")
+    )?;
+    Ok(())
+}
+
+impl<K: Display + Clone + Ord> LambdaModel<K> {
+    /// writes the expectation stack top-down, with `prefix` keeping node IDs unique
+    pub fn write_dot_nodes(
+        &self,
+        graph: &mut String,
+        prefix: &str,
+        style: &RenderStyle<K>,
+    ) -> Result<()> {
+        let mut previous = None;
+        for (index, expect) in self.get_expects().iter().rev().enumerate() {
+            let id = format!("{prefix}{index}");
+            expect.write_dot(graph, &id, style)?;
+            if let Some(previous) = previous {
+                writeln!(
+                    graph,
+                    r#"    "{}" -> "{}" [style=dotted, arrowhead=none];"#,
+                    previous, id
+                )?;
+            }
+            previous = Some(id);
+        }
+        Ok(())
+    }
+
+    pub fn to_dot_graph(&self, style: &RenderStyle<K>) -> Result<String> {
+        let mut graph = style.to_dot_header();
+        self.write_dot_nodes(&mut graph, "", style)?;
+        graph.push_str("}\n");
+        Ok(graph)
+    }
+
+    pub fn to_png(&self, filename: String, style: &RenderStyle<K>) -> Result<()> {
+        if !is_dot_installed() {
+            return Err(Error::GraphvizDotNotInstalled);
+        }
+        let dot = self.to_dot_graph(style)?;
+
+        exec_dot(dot, vec![Format::Png.into(), CommandArg::Output(filename)])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cognitive::CognitiveModel;
+    use crate::syntax::FeatureSet;
+
+    #[test]
+    fn lambda_to_dot_graph() {
+        let mut model = LambdaModel::init(FeatureSet::from_category("Sentence"));
+        model.receive("the").unwrap();
+        let dot = model.to_dot_graph(&RenderStyle::default()).unwrap();
+        assert!(dot.contains(r#""0" [label="the", color="lightgreen"];"#));
+        assert!(dot.contains(r#""0." [label="Sentence", color="lightblue"];"#));
+        assert!(dot.contains(r#""0" -> "0." [label="λ"];"#));
+    }
+}
@@ -1,7 +1,9 @@
 mod error;
+mod graph;
 mod node;
 mod r#struct;
 mod valid_entry;
 
 pub use error::Error;
+pub use node::Node;
 pub use r#struct::LambdaModel;
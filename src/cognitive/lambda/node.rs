@@ -1,33 +1,177 @@
-use crate::syntax::{FeatureSet, SyntaxValue};
+use crate::syntax::{Bindings, FeatureSet, SyntaxValue, Variable};
+use std::collections::BTreeSet;
 use std::fmt::Display;
 
-#[derive(Debug, Clone)]
+/// an argument of a lambda, marked if it is the copy a `MOVED()` entry left at its base position
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Argument<K> {
+    pub value: SyntaxValue<K>,
+    pub moved: bool,
+    /// whether tokens were taken in front of it while it was wondered about; it could have
+    /// taken a plain entry before them, so it takes none after them
+    pub overtaken: bool,
+    /// whether it stands behind tokens taken in front of it in this lambda, so that it is not
+    /// reordered in front of them again
+    pub behind: bool,
+}
+
+impl<K> Argument<K> {
+    pub fn moved(value: SyntaxValue<K>) -> Self {
+        Self {
+            value,
+            moved: true,
+            overtaken: false,
+            behind: false,
+        }
+    }
+}
+
+impl<K> From<SyntaxValue<K>> for Argument<K> {
+    fn from(value: SyntaxValue<K>) -> Self {
+        Self {
+            value,
+            moved: false,
+            overtaken: false,
+            behind: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Node<K> {
     Value {
         value: SyntaxValue<K>,
     },
+    /// `(A -> (B -> C))` is kept as `{ from: [A, B], to: C }`,
+    /// so `from` is never empty and `to` is never a lambda
     Lambda {
-        from: SyntaxValue<K>,
+        from: Vec<Argument<K>>,
         to: Box<Node<K>>,
     },
     Projection {
         ignore: FeatureSet<K>,
     },
+    /// a dangling `(A ->)`, left over when `([A, B] -> B)` is satisfied by `B`;
+    /// `A` becomes the first argument of whatever is inserted next
+    From {
+        from: Vec<Argument<K>>,
+    },
 }
 
 impl<K> Node<K> {
+    /// `(from -> to)`, curried into a single lambda if `to` is a lambda itself
+    pub fn lambda(from: impl Into<Argument<K>>, to: Node<K>) -> Self {
+        let from = from.into();
+        match to {
+            Node::Lambda { from: mut rest, to } => {
+                rest.insert(0, from);
+                Node::Lambda { from: rest, to }
+            }
+            to => Node::Lambda {
+                from: vec![from],
+                to: Box::new(to),
+            },
+        }
+    }
+
+    /// `(from[0] -> (from[1] -> ... to))`, or just `to` if `from` is empty
+    pub fn curry(from: Vec<Argument<K>>, to: Node<K>) -> Self {
+        from.into_iter()
+            .rev()
+            .fold(to, |to, from| Node::lambda(from, to))
+    }
+
+    /// splits `(A -> (B -> C))` into `A` and `(B -> C)`
+    pub fn split_lambda(self) -> Option<(Argument<K>, Node<K>)> {
+        match self {
+            Node::Lambda { mut from, to } => {
+                let first = from.remove(0);
+                match from.is_empty() {
+                    true => Some((first, *to)),
+                    false => Some((first, Node::Lambda { from, to })),
+                }
+            }
+            _ => None,
+        }
+    }
+
     pub fn get_features_left_mut(&mut self) -> Option<&mut FeatureSet<K>> {
         match self {
             Node::Value {
                 value: SyntaxValue::Features(fs),
             } => Some(fs),
-            Node::Lambda {
-                from: SyntaxValue::Features(fs),
-                ..
-            } => Some(fs),
+            Node::Lambda { from, .. } => match from.first_mut().map(|from| &mut from.value) {
+                Some(SyntaxValue::Features(fs)) => Some(fs),
+                _ => None,
+            },
             _ => None,
         }
     }
+
+    /// every feature set in this node
+    pub fn features(&self) -> Vec<&FeatureSet<K>> {
+        match self {
+            Node::Value { value } => value.try_into().into_iter().collect(),
+            Node::Lambda { from, to } => {
+                let mut features = values(from);
+                features.extend(to.features());
+                features
+            }
+            Node::Projection { ignore } => vec![ignore],
+            Node::From { from } => values(from),
+        }
+    }
+
+    pub fn features_mut(&mut self) -> Vec<&mut FeatureSet<K>> {
+        match self {
+            Node::Value {
+                value: SyntaxValue::Features(fs),
+            } => vec![fs],
+            Node::Value { .. } => Vec::new(),
+            Node::Lambda { from, to } => {
+                let mut features = values_mut(from);
+                features.extend(to.features_mut());
+                features
+            }
+            Node::Projection { ignore } => vec![ignore],
+            Node::From { from } => values_mut(from),
+        }
+    }
+}
+
+fn values<K>(arguments: &[Argument<K>]) -> Vec<&FeatureSet<K>> {
+    arguments
+        .iter()
+        .filter_map(|argument| (&argument.value).try_into().ok())
+        .collect()
+}
+
+pub fn values_mut<K>(arguments: &mut [Argument<K>]) -> Vec<&mut FeatureSet<K>> {
+    let features = arguments
+        .iter_mut()
+        .filter_map(|argument| match &mut argument.value {
+            SyntaxValue::Features(fs) => Some(fs),
+            SyntaxValue::Item(_) => None,
+        });
+    features.collect()
+}
+
+impl<K: Clone + Ord> Node<K> {
+    pub fn substitute(&mut self, bindings: &Bindings<K>) {
+        for fs in self.features_mut() {
+            fs.substitute(bindings);
+        }
+    }
+
+    pub fn next_variable(&self) -> Variable {
+        let variables = self.features().into_iter().map(FeatureSet::next_variable);
+        variables.max().unwrap_or(0)
+    }
+
+    pub fn variables(&self) -> BTreeSet<Variable> {
+        let features = self.features().into_iter();
+        features.flat_map(FeatureSet::variables).collect()
+    }
 }
 
 impl<K> From<FeatureSet<K>> for Node<K> {
@@ -44,16 +188,24 @@ impl<K> TryFrom<ValidEntry<K>> for Node<K> {
 
     fn try_from(value: ValidEntry<K>) -> Result<Self, Self::Error> {
         match value {
-            ValidEntry::Features(fs) => Ok(Node::from(fs)),
+            ValidEntry::Features(fs) | ValidEntry::Moved(fs) => Ok(Node::from(fs)),
             ValidEntry::Lambda { from, to, .. } => {
-                let from = ValidEntry::try_into(*from)?;
-                let to = Box::new(Node::from(to));
-                Ok(Node::Lambda { from, to })
+                let from = Argument::try_from(*from)?;
+                Ok(Node::lambda(from, Node::try_from(*to)?))
             }
         }
     }
 }
 
+impl<K: Display> Display for Argument<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.moved {
+            true => write!(f, "MOVED({})", self.value),
+            false => write!(f, "{}", self.value),
+        }
+    }
+}
+
 impl<K: Display> Display for Node<K> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -61,11 +213,43 @@ impl<K: Display> Display for Node<K> {
                 write!(f, "{}", value)
             }
             Node::Lambda { from, to } => {
-                write!(f, "λ({from} -> {to})")
+                let from = from.iter().map(|from| format!("{from}"));
+                write!(f, "λ({} -> {to})", from.collect::<Vec<_>>().join(", "))
             }
             Node::Projection { ignore } => {
                 write!(f, ">>(ignore: {ignore})")
             }
+            Node::From { from } => {
+                let from = from.iter().map(|from| format!("{from}"));
+                write!(f, "λ({} ->)", from.collect::<Vec<_>>().join(", "))
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn features(category: &'static str) -> Argument<&'static str> {
+        Argument::from(SyntaxValue::Features(FeatureSet::from_category(category)))
+    }
+
+    #[test]
+    fn curry_and_split_lambda() {
+        let c = Node::from(FeatureSet::from_category("C"));
+        let lambda = Node::lambda(features("A"), Node::lambda(features("B"), c));
+        assert_eq!(format!("{lambda}"), "λ(A, B -> C)");
+
+        let (a, rest) = lambda.split_lambda().unwrap();
+        assert_eq!(format!("{a}"), "A");
+        assert_eq!(format!("{rest}"), "λ(B -> C)");
+        let (b, rest) = rest.split_lambda().unwrap();
+        assert_eq!(format!("{b}"), "B");
+        assert!(rest.split_lambda().is_none());
+
+        let c = Node::from(FeatureSet::from_category("C"));
+        let curried = Node::curry(vec![features("A"), features("B")], c);
+        assert_eq!(format!("{curried}"), "λ(A, B -> C)");
+    }
+}
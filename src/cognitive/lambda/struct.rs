@@ -1,45 +1,113 @@
 use super::super::CognitiveModel;
 use super::error::{Error, Result};
-use super::node::Node;
+use super::node::{Argument, Node, values_mut};
 use super::valid_entry::ValidEntry;
-use crate::syntax::{FeatureSet, SyntaxValue};
+use crate::syntax::{Bindings, FeatureSet, SyntaxValue, Variable};
 use std::fmt::{Debug, Display};
 
 #[derive(Debug, Clone)]
 pub struct LambdaModel<K> {
     expects: Vec<Node<K>>,
+    /// set when the expectation on top was pushed together with the lambda below it by the
+    /// last entry, so that it may still take what that lambda has in hand as its corner;
+    /// holds whether the corner may be all that it expects, as for the entry of a moved copy,
+    /// which stands at its base position before what is in hand
+    corner: Option<bool>,
 }
 
 impl<K: Clone + Ord> LambdaModel<K> {
     fn new() -> Self {
         Self {
             expects: Vec::new(),
+            corner: None,
         }
     }
     fn is_empty(&self) -> bool {
         self.expects.is_empty()
     }
+    /// the expectation stack, bottom first
+    pub fn get_expects(&self) -> &[Node<K>] {
+        &self.expects
+    }
+    pub fn from_expects(expects: Vec<Node<K>>, corner: Option<bool>) -> Self {
+        Self { expects, corner }
+    }
+    pub fn get_corner(&self) -> Option<bool> {
+        self.corner
+    }
+    pub fn into_expects(self) -> Vec<Node<K>> {
+        self.expects
+    }
+    /// a dangling `(A ->)` on top becomes the first argument of the inserted node
     fn push(&mut self, expect: Node<K>) {
-        self.expects.push(expect);
+        match (
+            self.expects.pop_if(|top| matches!(top, Node::From { .. })),
+            expect,
+        ) {
+            (Some(Node::From { from }), expect @ (Node::Value { .. } | Node::Lambda { .. })) => {
+                self.expects.push(Node::curry(from, expect))
+            }
+            (dangling, expect) => {
+                self.expects.extend(dangling);
+                self.expects.push(expect);
+            }
+        }
     }
-    fn push_lambda(&mut self, from: SyntaxValue<K>, to: Node<K>) {
-        self.push(Node::Lambda {
-            from,
-            to: Box::new(to),
-        });
-    }
-    fn push_lambda_features(&mut self, from: FeatureSet<K>, to: FeatureSet<K>) -> Result<bool> {
-        if to.is_subset(&from) {
-            self.possibly_project(&from)?;
-            Ok(false)
-        } else {
-            self.push_lambda(SyntaxValue::from(from), Node::from(to));
-            Ok(true)
+    /// returns whether a lambda was pushed, i.e., the last argument does not satisfy `to` yet;
+    /// if it does, the arguments before it are left dangling
+    fn push_lambda(&mut self, from: impl Into<Argument<K>>, to: Node<K>) -> Result<bool> {
+        let Node::Lambda { from, to } = Node::lambda(from, to) else {
+            unreachable!("Node::lambda always returns a lambda")
+        };
+        self.push_unless_satisfied(from, to)
+    }
+    fn push_unless_satisfied(
+        &mut self,
+        mut from: Vec<Argument<K>>,
+        to: Box<Node<K>>,
+    ) -> Result<bool> {
+        let mut bindings = Bindings::new();
+        let satisfied = match (from.last().map(|last| &last.value), to.as_ref()) {
+            (
+                Some(SyntaxValue::Features(last)),
+                Node::Value {
+                    value: SyntaxValue::Features(to),
+                },
+            ) => to.unify(last, &mut bindings),
+            _ => false,
+        };
+        if !satisfied {
+            self.push(Node::Lambda { from, to });
+            return Ok(true);
+        }
+
+        self.substitute(&bindings);
+        for fs in values_mut(&mut from) {
+            fs.substitute(&bindings);
+        }
+
+        if let Some(SyntaxValue::Features(last)) = from.pop().map(|last| last.value) {
+            self.possibly_project(&last)?;
+        }
+        if !from.is_empty() {
+            self.push(Node::From { from });
         }
+        Ok(false)
     }
     fn push_projection(&mut self, ignore: FeatureSet<K>) {
         self.push(Node::Projection { ignore });
     }
+    /// binds the variables in every expectation
+    fn substitute(&mut self, bindings: &Bindings<K>) {
+        for expect in self.expects.iter_mut() {
+            expect.substitute(bindings);
+        }
+    }
+    /// the smallest variable not in any expectation, to rename the variables of an entry apart
+    fn next_variable(&self) -> Variable {
+        let variables = self.expects.iter().map(Node::next_variable);
+        variables.max().unwrap_or(0)
+    }
     fn peek(&self) -> Option<&Node<K>> {
         self.expects.last()
     }
@@ -52,6 +120,91 @@ impl<K: Clone + Ord> LambdaModel<K> {
     fn pop_node(&mut self) -> Result<Node<K>> {
         self.pop().ok_or(Error::NoExpectation)
     }
+    /// whether tokens were taken in front of the first argument of the expectation on top
+    fn overtaken(&self) -> bool {
+        match self.peek() {
+            Some(Node::Lambda { from, .. }) => from.first().is_some_and(|from| from.overtaken),
+            _ => false,
+        }
+    }
+    /// the lambda that the expectation on top is to become an argument of,
+    /// i.e., `(B -> (C -> D))` in `(A, (B -> (C -> D)))`
+    fn parent(&self) -> Option<usize> {
+        let mut below = self.expects.len().checked_sub(2)?;
+        if let Node::Projection { .. } = self.expects[below] {
+            below = below.checked_sub(1)?;
+        }
+        matches!(self.expects[below], Node::Lambda { .. }).then_some(below)
+    }
+    /// what the parent has in hand besides its first argument, up to the last of it that may
+    /// be a corner of the expectation on top
+    fn parent_pending(&self, whole: bool) -> usize {
+        match self.parent().map(|parent| &self.expects[parent]) {
+            Some(Node::Lambda { from, .. }) => from
+                .iter()
+                .rposition(|from| self.is_corner(from, whole))
+                .unwrap_or(0),
+            _ => 0,
+        }
+    }
+    /// anything may go behind a moved copy the expectation on top has in hand, and a moved
+    /// copy may be the leftmost part of a value pushed for another one; anything else in hand
+    /// could have taken an entry of its own by a plain decide, so it may only be all of the
+    /// expectation, and only if `whole`
+    fn is_corner(&self, corner: &Argument<K>, whole: bool) -> bool {
+        let SyntaxValue::Features(corner_fs) = &corner.value else {
+            return false;
+        };
+        let (lambda_top, behind_moved, to) = match self.peek() {
+            Some(Node::Lambda { from, to }) => (true, from[0].moved, to.as_ref()),
+            Some(expected) => (false, false, expected),
+            None => return false,
+        };
+        match to {
+            Node::Value {
+                value: SyntaxValue::Features(to),
+            } if to.unify(corner_fs, &mut Bindings::new()) => whole,
+            _ => behind_moved || (corner.moved && whole && !lambda_top),
+        }
+    }
+    /// an argument the parent has in hand becomes the corner of the expectation on top,
+    /// i.e., `(A, (B -> (C -> D)))` is treated as `((C -> A), (B -> D))`; it goes behind
+    /// what the expectation has in hand already, as `(C -> (X -> A))` is `(X -> (C -> A))`
+    fn take_corner(&mut self, index: usize, whole: bool) -> Result<()> {
+        let parent = self.parent().ok_or(Error::CannotReorder(index))?;
+        let corner = match &self.expects[parent] {
+            Node::Lambda { from, .. } => from.get(index),
+            _ => None,
+        };
+        if !corner.is_some_and(|corner| self.is_corner(corner, whole)) {
+            Err(Error::CannotReorder(index))?
+        }
+        let corner = match &mut self.expects[parent] {
+            Node::Lambda { from, .. } => from.remove(index),
+            _ => unreachable!("Already checked that the parent is a lambda"),
+        };
+        // nothing was taken in front of it in the expectation it joins
+        let corner = Argument {
+            behind: false,
+            ..corner
+        };
+        let expected = self.pop_node()?;
+        // unless it waits for a projection, the parent is checked against its target again
+        if let Some(Node::Lambda { from, to }) = self
+            .expects
+            .pop_if(|top| matches!(top, Node::Lambda { .. }))
+        {
+            self.push_unless_satisfied(from, to)?;
+        }
+        match expected {
+            Node::Lambda { mut from, to } => {
+                from.insert(1, corner);
+                self.push_unless_satisfied(from, to)?
+            }
+            expected => self.push_lambda(corner, expected)?,
+        };
+        Ok(())
+    }
     fn possibly_project(&mut self, from: &FeatureSet<K>) -> Result<()> {
         if let Some(Node::Projection { .. }) = self.peek() {
             let ignore = match self.pop() {
@@ -59,10 +212,16 @@ impl<K: Clone + Ord> LambdaModel<K> {
                 _ => unreachable!("Already checked that expect stack top is Projection"),
             };
 
-            if let Some(node) = self.peek_mut() {
-                if let Some(onto) = node.get_features_left_mut() {
-                    FeatureSet::project(from, onto, &ignore)?;
-                }
+            if let Some(onto) = self.peek_mut().and_then(Node::get_features_left_mut) {
+                FeatureSet::project(from, onto, &ignore)?;
+            }
+            // the lambda projected onto was kept even if it satisfied its target,
+            // which it is only checked against now
+            if let Some(Node::Lambda { from, to }) = self
+                .expects
+                .pop_if(|top| matches!(top, Node::Lambda { .. }))
+            {
+                self.push_unless_satisfied(from, to)?;
             }
         }
         Ok(())
@@ -81,59 +240,88 @@ mod lexicon {
     use crate::lexicon::LexiconEntry;
     impl<K: Clone + Ord> LambdaModel<K> {
         pub fn push_lexicon_lambda(&mut self, from: ValidEntry<K>, to: Node<K>) -> Result<bool> {
-            // do not add projection if insertion fails due to being subset
-
             match (from, to) {
-                (ValidEntry::Features(from), Node::Value { value: to }) => match to {
-                    SyntaxValue::Features(to) => self.push_lambda_features(from, to),
+                (
+                    from @ (ValidEntry::Features(_) | ValidEntry::Moved(_)),
+                    Node::Value { value: to },
+                ) => match to {
+                    SyntaxValue::Features(to) => {
+                        self.push_lambda(Argument::try_from(from)?, Node::from(to))
+                    }
                     _ => Err(Error::LambdaToMustBeFeatures),
                 },
-                (ValidEntry::Features(from), to @ Node::Lambda { .. }) => {
-                    let from = SyntaxValue::from(from);
-                    self.push_lambda(from, to);
-                    Ok(true)
-                }
+                (
+                    from @ (ValidEntry::Features(_) | ValidEntry::Moved(_)),
+                    to @ Node::Lambda { .. },
+                ) => self.push_lambda(Argument::try_from(from)?, to),
+                // `((A > B) > (C -> D))` expects `A`, and then `B` takes what is in hand, i.e.,
+                // `(A, (B -> (C -> D)))`
                 (
                     ValidEntry::Lambda {
                         from: new,
                         to: from,
                         project,
                     },
-                    Node::Value { value: to },
+                    to @ (Node::Value { .. } | Node::Lambda { .. }),
                 ) => {
-                    let to = match to {
-                        SyntaxValue::Features(to) => to,
-                        _ => Err(Error::LambdaToMustBeFeatures)?,
-                    };
-                    if self.push_lambda_features(from, to)? && project {
-                        let ignore = new.get_features_right();
-                        self.push_projection(ignore.clone());
-                    }
-
-                    let new = Node::try_from(*new)?;
-                    self.push(new);
+                    let pushed =
+                        self.push_lexicon_lambda_projected(*from, to, project.then_some(&new))?;
+                    self.push_expected(*new)?;
+                    self.corner = pushed.then_some(false);
                     Ok(true)
                 }
-                (
-                    ValidEntry::Lambda {
-                        from: a,
-                        to: b,
-                        project: project_ab,
-                    },
-                    Node::Lambda { from: c, to: d },
-                ) => {
-                    let b = ValidEntry::from(b);
-                    if self.push_lexicon_lambda(b, *d)? && project_ab {
-                        let ignore = (*a).get_features_right();
-                        self.push_projection(ignore.clone());
-                    }
+                (_, Node::Projection { .. } | Node::From { .. }) => {
+                    Err(Error::LambdaToMustBeFeatures)
+                }
+            }
+        }
 
-                    let a = Node::try_from(*a)?;
-                    self.push_lambda(c, a);
-                    Ok(true)
+        /// `from` will be projected onto `to` once it is complete; until then, `to` is kept
+        /// even if it satisfies `target` already, as the projection may conflict with it;
+        /// returns whether a lambda was pushed
+        fn push_lexicon_lambda_projected(
+            &mut self,
+            to: ValidEntry<K>,
+            target: Node<K>,
+            from: Option<&ValidEntry<K>>,
+        ) -> Result<bool> {
+            let Some(from) = from else {
+                return self.push_lexicon_lambda(to, target);
+            };
+            if matches!(to, ValidEntry::Lambda { .. }) {
+                return Err(Error::ProjectOntoLambda);
+            }
+            match target {
+                Node::Value {
+                    value: SyntaxValue::Features(_),
+                }
+                | Node::Lambda { .. } => {
+                    self.push(Node::lambda(
+                        SyntaxValue::from(to.get_features_right().clone()),
+                        target,
+                    ));
                 }
-                (_, Node::Projection { .. }) => Err(Error::LambdaToMustBeFeatures),
+                _ => Err(Error::LambdaToMustBeFeatures)?,
             }
+            self.push_projection(from.get_features_right().clone());
+            Ok(true)
+        }
+
+        /// expects `entry` from the upcoming tokens; for `((A > B) > C)`, `A` is expected first
+        /// and then `B` is a corner of `C`
+        fn push_expected(&mut self, entry: ValidEntry<K>) -> Result<()> {
+            let expected = match entry {
+                ValidEntry::Lambda { from, to, .. }
+                    if matches!(*from, ValidEntry::Lambda { .. }) =>
+                {
+                    let from_right = SyntaxValue::from(from.get_features_right().clone());
+                    self.push(Node::lambda(from_right, Node::try_from(*to)?));
+                    return self.push_expected(*from);
+                }
+                entry => Node::try_from(entry)?,
+            };
+            self.push(expected);
+            Ok(())
         }
     }
 
@@ -152,8 +340,8 @@ mod lexicon {
         fn demand(&self) -> bool {
             match self.peek() {
                 Some(node) => match node {
-                    Node::Value { .. } => true,
-                    Node::Lambda { to, .. } => matches!(&**to, Node::Value { .. }),
+                    Node::Value { .. } | Node::From { .. } => true,
+                    Node::Lambda { from, .. } => from.len() == 1,
                     _ => false,
                 },
                 None => false,
@@ -161,48 +349,127 @@ mod lexicon {
         }
 
         fn receive(&mut self, token: K) -> super::super::super::error::Result<()> {
-            let from = SyntaxValue::from(token);
-            let to = self.pop_node()?;
-            self.push_lambda(from, to);
+            self.corner = None;
+            let from = Argument::from(SyntaxValue::from(token));
+            // a dangling `(A ->)` is followed by the token, e.g., `λ(A, token -> to)`
+            let expect = match self.pop_node()? {
+                Node::From { from: mut dangling } => {
+                    dangling.push(from);
+                    Node::curry(dangling, self.pop_node()?)
+                }
+                Node::Lambda {
+                    from: mut wondered,
+                    to,
+                } => {
+                    for wondered in wondered.iter_mut() {
+                        wondered.overtaken = true;
+                        wondered.behind = true;
+                    }
+                    Node::lambda(from, Node::Lambda { from: wondered, to })
+                }
+                to => Node::lambda(from, to),
+            };
+            self.push(expect);
             Ok(())
         }
 
         fn wonder(&self) -> Option<&SyntaxValue<K>> {
             match self.peek() {
-                Some(Node::Lambda { from, .. }) => Some(from),
+                Some(Node::Lambda { from, .. }) => from.first().map(|from| &from.value),
                 _ => None,
             }
         }
 
-        fn decide(&mut self, entry: LexiconEntry<K>) -> super::super::super::error::Result<()> {
-            let target = self.pop_node()?;
-            match target {
-                Node::Lambda {
-                    from: original_from,
-                    to,
-                } => match (original_from, entry) {
+        fn moved(&self) -> bool {
+            match self.peek() {
+                Some(Node::Lambda { from, .. }) => from.first().is_some_and(|from| from.moved),
+                _ => false,
+            }
+        }
+
+        fn pending(&self) -> usize {
+            if let Some(whole) = self.corner {
+                // followed by what the parent has in hand, which may become its corner
+                return 1 + self.parent_pending(whole);
+            }
+            match self.peek() {
+                Some(Node::Lambda { from, .. }) => match from.first().map(|from| &from.value) {
+                    Some(SyntaxValue::Features(_)) => from.len(),
+                    _ => 1,
+                },
+                _ => 0,
+            }
+        }
+
+        fn reorder(&mut self, index: usize) -> super::super::super::error::Result<()> {
+            if let Some(whole) = self.corner.take() {
+                return Ok(self.take_corner(index, whole)?);
+            }
+            match self.peek_mut() {
+                // tokens taken in front of an argument stand before it
+                Some(Node::Lambda { from, .. }) => match from.get(index) {
+                    Some(Argument {
+                        value: SyntaxValue::Features(_),
+                        behind: false,
+                        ..
+                    }) => {
+                        let argument = from.remove(index);
+                        from.insert(0, argument);
+                        Ok(())
+                    }
+                    _ => Err(Error::CannotReorder(index))?,
+                },
+                _ => Err(Error::ApplyEntryToNonLambda)?,
+            }
+        }
+
+        fn decide(&mut self, mut entry: LexiconEntry<K>) -> super::super::super::error::Result<()> {
+            self.corner = None;
+            let moved = self.moved();
+            let overtaken = self.overtaken();
+            entry.offset_variables(self.next_variable());
+            let mut target = self.pop_node()?;
+            // the key binds the variables in hand, and the features in hand those of the entry
+            if let Some(query) = target.get_features_left_mut() {
+                let bindings = entry.bind(query).ok_or(Error::KeyDoesNotUnify)?;
+                self.substitute(&bindings);
+                target.substitute(&bindings);
+            }
+            match target.split_lambda() {
+                Some((original_from, to)) => match (original_from.value, entry) {
                     (
                         SyntaxValue::Features(from_fs),
                         LexiconEntry::Functional {
                             to: from,
                             project: entry_project,
+                            key,
                         },
                     ) => {
                         let mut from = ValidEntry::try_from(from)?;
+                        if overtaken && matches!(from, ValidEntry::Features(_)) {
+                            Err(Error::Overtaken)?
+                        }
+                        from.project_onto_moved(&from_fs, &key)?;
                         if let Some(ignore_fs) = entry_project {
                             let onto_fs = from.get_features_right_mut();
                             FeatureSet::project(&from_fs, onto_fs, &ignore_fs)
                                 .map_err(Error::Syntax)?;
                         }
-                        self.push_lexicon_lambda(from, *to)?;
+                        self.push_lexicon_lambda(from, to)?;
                     }
-                    (SyntaxValue::Item(_), LexiconEntry::Lexical(from)) => {
+                    (
+                        SyntaxValue::Item(_),
+                        LexiconEntry::Lexical(from, _) | LexiconEntry::Guess(from),
+                    ) => {
                         let from = ValidEntry::try_from(from)?;
-                        self.push_lexicon_lambda(from, *to)?;
+                        self.push_lexicon_lambda(from, to)?;
                     }
                     _ => Err(Error::QueryAndEntryTypeMismatch)?,
                 },
-                _ => Err(Error::ApplyEntryToNonLambda)?,
+                None => Err(Error::ApplyEntryToNonLambda)?,
+            }
+            if let Some(whole) = self.corner.as_mut() {
+                *whole = moved;
             }
             Ok(())
         }
@@ -221,3 +488,127 @@ impl<K: Display> Display for LambdaModel<K> {
         write!(f, "{}", formatted)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexicon::{LexiconEntry, LexiconNode};
+
+    fn node(category: &'static str) -> LexiconNode<&'static str> {
+        LexiconNode::Value {
+            value: SyntaxValue::Features(FeatureSet::from_category(category)),
+        }
+    }
+
+    fn lambda(
+        from: LexiconNode<&'static str>,
+        to: LexiconNode<&'static str>,
+    ) -> LexiconNode<&'static str> {
+        LexiconNode::Lambda {
+            from: Box::new(from),
+            to: Box::new(to),
+            project: false,
+        }
+    }
+
+    #[test]
+    fn decide_higher_order_entry() {
+        let mut model = LambdaModel::init(FeatureSet::from_category("D"));
+        model.receive("k").unwrap();
+        // k = (A > (C > D)), i.e., k takes A and then C to become D
+        let entry = lambda(node("A"), lambda(node("C"), node("D")));
+        model.decide(LexiconEntry::Lexical(entry, None)).unwrap();
+        assert_eq!(format!("{model}"), "A, C");
+
+        // k = (((A > B) > C) > D), i.e., a B is built from A first, then a C from that B
+        let mut model = LambdaModel::init(FeatureSet::from_category("D"));
+        model.receive("k").unwrap();
+        let entry = lambda(lambda(lambda(node("A"), node("B")), node("C")), node("D"));
+        model.decide(LexiconEntry::Lexical(entry, None)).unwrap();
+        assert_eq!(format!("{model}"), "λ(A -> B), λ(B -> C)");
+    }
+
+    #[test]
+    fn decide_lambda_onto_lambda() {
+        let in_hand = |category| {
+            let c = Argument::from(SyntaxValue::Features(FeatureSet::from_category(category)));
+            let expects = Node::curry(
+                vec![Argument::from(SyntaxValue::from("k")), c],
+                Node::from(FeatureSet::from_category("D")),
+            );
+            LambdaModel::from_expects(vec![expects], None)
+        };
+        // k = (A > B) with C in hand for D, i.e., `((A > B) > (C > D))`
+        let mut model = in_hand("C");
+        let entry = lambda(node("A"), node("B"));
+        model
+            .decide(LexiconEntry::Lexical(entry.clone(), None))
+            .unwrap();
+        assert_eq!(format!("{model}"), "A, λ(B, C -> D)");
+
+        // C could have taken an entry of its own instead, so it is no corner of A
+        assert_eq!(model.pending(), 1);
+        assert!(model.reorder(1).is_err());
+
+        // k = ((MOVED(A) > B) > E), after which C may still go behind the moved copy of A
+        let mut model = in_hand("C");
+        let moved = LexiconNode::Moved {
+            from: FeatureSet::from_category("A"),
+        };
+        let entry = lambda(lambda(moved, node("B")), node("E"));
+        model.decide(LexiconEntry::Lexical(entry, None)).unwrap();
+        assert_eq!(format!("{model}"), "λ(MOVED(A) -> B), λ(E, C -> D)");
+        assert_eq!(model.pending(), 2);
+        model.reorder(1).unwrap();
+        assert_eq!(format!("{model}"), "λ(MOVED(A), C -> B), λ(E -> D)");
+    }
+
+    #[test]
+    fn decide_binds_variables() {
+        // DetH-number = (NounP-number > DetP-number), applied to DetH-number:sg
+        let features = |category, number: Option<usize>| {
+            let mut fs = FeatureSet::from_category(category);
+            match number {
+                Some(variable) => fs.insert_variable("number", variable),
+                None => fs.insert("number", Some("sg")),
+            }
+            fs
+        };
+        let entry = LexiconEntry::Functional {
+            to: lambda(
+                LexiconNode::Value {
+                    value: SyntaxValue::from(features("NounP", Some(0))),
+                },
+                LexiconNode::Value {
+                    value: SyntaxValue::from(features("DetP", Some(0))),
+                },
+            ),
+            project: None,
+            key: features("DetH", Some(0)),
+        };
+        let mut model = LambdaModel::init(FeatureSet::from_category("DetP"));
+        model.expects = vec![Node::lambda(
+            SyntaxValue::from(features("DetH", None)),
+            Node::from(FeatureSet::from_category("DetP")),
+        )];
+        model.decide(entry).unwrap();
+        // the result DetP--number:sg satisfies the target right away
+        assert_eq!(format!("{model}"), "NounP--number:sg");
+    }
+
+    #[test]
+    fn dangling_from_attaches_to_next() {
+        let mut model = LambdaModel::init(FeatureSet::from_category("S"));
+        let b = Node::from(FeatureSet::from_category("B"));
+        let a = SyntaxValue::Features(FeatureSet::from_category("A"));
+        let pushed = model.push_lambda(
+            a,
+            Node::lambda(SyntaxValue::from(FeatureSet::from_category("B")), b),
+        );
+        assert!(!pushed.unwrap());
+        assert_eq!(format!("{model}"), "λ(A ->), S");
+
+        model.receive("t").unwrap();
+        assert_eq!(format!("{model}"), "λ(A, t -> S)");
+    }
+}
@@ -6,9 +6,12 @@ use crate::syntax::{FeatureSet, SyntaxValue::Features};
 
 pub enum ValidEntry<K> {
     Features(FeatureSet<K>),
+    /// a copy of the constituent that triggered the entry, left at its base position
+    Moved(FeatureSet<K>),
+    /// `to` is a lambda itself for higher-order entries, e.g., `(A > (B > C))`
     Lambda {
         from: Box<ValidEntry<K>>,
-        to: FeatureSet<K>,
+        to: Box<ValidEntry<K>>,
         project: bool,
     },
 }
@@ -17,13 +20,35 @@ impl<K> ValidEntry<K> {
     pub fn get_features_right(&self) -> &FeatureSet<K> {
         match self {
             ValidEntry::Features(fs) => fs,
-            ValidEntry::Lambda { to, .. } => to,
+            ValidEntry::Moved(fs) => fs,
+            ValidEntry::Lambda { to, .. } => to.get_features_right(),
         }
     }
     pub fn get_features_right_mut(&mut self) -> &mut FeatureSet<K> {
         match self {
             ValidEntry::Features(fs) => fs,
-            ValidEntry::Lambda { to, .. } => to,
+            ValidEntry::Moved(fs) => fs,
+            ValidEntry::Lambda { to, .. } => to.get_features_right_mut(),
+        }
+    }
+}
+
+impl<K: Clone + Ord> ValidEntry<K> {
+    /// projects the trigger's features, except those the entry was looked up by,
+    /// onto every moved copy
+    pub fn project_onto_moved(
+        &mut self,
+        trigger: &FeatureSet<K>,
+        key: &FeatureSet<K>,
+    ) -> Result<(), super::Error> {
+        match self {
+            ValidEntry::Features(_) => Ok(()),
+            ValidEntry::Moved(fs) => FeatureSet::project(trigger, fs, key)
+                .map_err(|_| super::Error::MovedCopyInconsistent),
+            ValidEntry::Lambda { from, to, .. } => {
+                from.project_onto_moved(trigger, key)?;
+                to.project_onto_moved(trigger, key)
+            }
         }
     }
 }
@@ -34,12 +59,13 @@ impl<K> From<FeatureSet<K>> for ValidEntry<K> {
     }
 }
 
-use crate::syntax::SyntaxValue;
-impl<K> TryInto<SyntaxValue<K>> for ValidEntry<K> {
+use super::node::Argument;
+impl<K> TryFrom<ValidEntry<K>> for Argument<K> {
     type Error = super::Error;
-    fn try_into(self) -> Result<SyntaxValue<K>, Self::Error> {
-        match self {
-            ValidEntry::Features(fs) => Ok(Features(fs)),
+    fn try_from(entry: ValidEntry<K>) -> Result<Self, Self::Error> {
+        match entry {
+            ValidEntry::Features(fs) => Ok(Argument::from(Features(fs))),
+            ValidEntry::Moved(fs) => Ok(Argument::moved(Features(fs))),
             ValidEntry::Lambda { .. } => Err(Self::Error::TypeConversion),
         }
     }
@@ -54,19 +80,17 @@ impl<K> TryFrom<LexiconNode<K>> for ValidEntry<K> {
             Value {
                 value: Features(fs),
             } => Ok(ValidEntry::Features(fs)),
-            Moved { from } => Ok(ValidEntry::Features(from)),
+            Moved { from } => Ok(ValidEntry::Moved(from)),
             Lambda { from, to, project } => {
                 let from = Self::try_from(*from)?;
                 let to = match *to {
-                    Value {
-                        value: Features(fs),
-                    } => fs,
-                    Moved { from } => from,
-                    _ => Err(err)?,
+                    // only copies on the `from` side are traces
+                    Moved { from } => ValidEntry::Features(from),
+                    to => Self::try_from(to)?,
                 };
                 Ok(ValidEntry::Lambda {
                     from: Box::new(from),
-                    to,
+                    to: Box::new(to),
                     project,
                 })
             }
@@ -74,3 +98,50 @@ impl<K> TryFrom<LexiconNode<K>> for ValidEntry<K> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moved_entry() -> ValidEntry<&'static str> {
+        let mut copy = FeatureSet::from_category("DetP");
+        copy.insert("case", Some("nocase"));
+        let node = Lambda {
+            from: Box::new(Moved { from: copy }),
+            to: Box::new(Value {
+                value: Features(FeatureSet::from_category("TenseB")),
+            }),
+            project: false,
+        };
+        ValidEntry::try_from(node).unwrap()
+    }
+
+    #[test]
+    fn project_trigger_onto_moved_copy() {
+        let mut trigger = FeatureSet::from_category("DetP");
+        trigger.insert("case", Some("subj"));
+        trigger.insert("number", Some("sg"));
+        let mut key = FeatureSet::from_category("DetP");
+        key.insert("case", Some("subj"));
+
+        let mut entry = moved_entry();
+        entry.project_onto_moved(&trigger, &key).unwrap();
+        let ValidEntry::Lambda { from, .. } = entry else {
+            panic!("not a lambda");
+        };
+        let mut expected = FeatureSet::from_category("DetP");
+        expected.insert("case", Some("nocase"));
+        expected.insert("number", Some("sg"));
+        assert_eq!(from.get_features_right(), &expected);
+    }
+
+    #[test]
+    fn project_conflicting_trigger_onto_moved_copy() {
+        let mut trigger = FeatureSet::from_category("DetP");
+        trigger.insert("case", Some("subj"));
+        let key = FeatureSet::from_category("DetP");
+
+        let mut entry = moved_entry();
+        assert!(entry.project_onto_moved(&trigger, &key).is_err());
+    }
+}
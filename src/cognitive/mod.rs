@@ -1,11 +1,17 @@
+mod chart;
 mod error;
+mod graph;
 mod lambda;
 mod model;
+mod style;
 mod tree;
 
+pub use chart::ChartModel;
 pub use error::Error;
+pub use graph::{Snapshot, is_dot_installed};
 pub use lambda::LambdaModel;
 pub use model::CognitiveModel;
+pub use style::RenderStyle;
 pub use tree::TreeModel;
 
 #[cfg(test)]
@@ -18,8 +18,19 @@ pub trait CognitiveModel<K>: Clone {
     /// what does the cognitive model wants interpretation for
     fn wonder(&self) -> Option<&SyntaxValue<K>>;
 
+    /// whether wonder() is the copy a `MOVED()` entry left at its base position
+    fn moved(&self) -> bool;
+
     /// apply an entry as an interpretation to the result of wonder()
     fn decide(&mut self, entry: LexiconEntry<K>) -> Result<()>;
+
+    /// how many arguments the current expectation is waiting for,
+    /// wonder() being the first of them
+    fn pending(&self) -> usize;
+
+    /// bring the pending argument at `index` forward so that wonder() returns it,
+    /// i.e., treat `(A -> (B -> C))` as `(B -> (A -> C))`
+    fn reorder(&mut self, index: usize) -> Result<()>;
 }
 
 #[cfg(test)]
@@ -48,9 +59,19 @@ pub mod naive_model {
         fn wonder(&self) -> Option<&SyntaxValue<GlobalKey>> {
             None
         }
+        fn moved(&self) -> bool {
+            false
+        }
         fn decide(&mut self, entry: LexiconEntry<GlobalKey>) -> Result<()> {
             println!("NaiveInterpreter: Applying entry {entry}");
             Ok(())
         }
+        fn pending(&self) -> usize {
+            0
+        }
+        fn reorder(&mut self, index: usize) -> Result<()> {
+            println!("NaiveInterpreter: Reordering argument {index}");
+            Ok(())
+        }
     }
 }
@@ -0,0 +1,4 @@
+mod parser;
+mod r#struct;
+
+pub use r#struct::RenderStyle;
@@ -0,0 +1,138 @@
+use super::RenderStyle;
+use pest::Parser;
+use pest::iterators::Pair;
+use pest_derive::Parser;
+use std::str::FromStr;
+
+#[derive(Parser)]
+#[grammar = "cognitive/style/style.pest"]
+struct StyleParser;
+
+impl<K: FromStr + Ord> FromStr for RenderStyle<K> {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let pairs = StyleParser::parse(Rule::style, input).map_err(|e| e.to_string())?;
+
+        let mut style = RenderStyle::default();
+        for pair in pairs {
+            let rule = pair.as_rule();
+            if rule == Rule::EOI {
+                continue;
+            }
+            for entry in pair.into_inner() {
+                let (key, values) = parse_entry(entry);
+                match rule {
+                    Rule::layout_section => parse_layout(&mut style, &key, values)?,
+                    Rule::labels_section => parse_labels(&mut style, &key, values)?,
+                    Rule::colors_section => {
+                        let category = parse_word(&key)?;
+                        style.colors.insert(category, single(&key, values)?);
+                    }
+                    r => unreachable!("Parsing style: unexpected rule: {r:?}"),
+                }
+            }
+        }
+        Ok(style)
+    }
+}
+
+fn parse_entry(pair: Pair<Rule>) -> (String, Vec<String>) {
+    let mut inner = pair.into_inner();
+    let key = inner.next().expect("Parsing style entry: no key");
+    let values = inner
+        .map(|value| match value.as_rule() {
+            Rule::quoted_value => value.into_inner().as_str().to_string(),
+            _ => value.as_str().trim().to_string(),
+        })
+        .collect();
+    (key.as_str().trim().to_string(), values)
+}
+
+fn parse_layout<K>(
+    style: &mut RenderStyle<K>,
+    key: &str,
+    values: Vec<String>,
+) -> Result<(), String> {
+    match key {
+        "orientation" => style.orientation = single(key, values)?.parse()?,
+        "shape" => style.shape = single(key, values)?,
+        "font" => style.font = Some(single(key, values)?),
+        "item_color" => style.item_color = single(key, values)?,
+        "feature_color" => style.feature_color = single(key, values)?,
+        _ => Err(format!("Unknown layout option {key}"))?,
+    }
+    Ok(())
+}
+
+fn parse_labels<K: FromStr + Ord>(
+    style: &mut RenderStyle<K>,
+    key: &str,
+    values: Vec<String>,
+) -> Result<(), String> {
+    match key {
+        "abbreviate" => {
+            style.abbreviate = single(key, values)?
+                .parse()
+                .map_err(|_| format!("{key} must be true or false"))?
+        }
+        "hide" => {
+            for value in values {
+                style.hidden.insert(parse_word(&value)?);
+            }
+        }
+        "order" => {
+            for value in values {
+                style.order.push(parse_word(&value)?);
+            }
+        }
+        _ => Err(format!("Unknown label option {key}"))?,
+    }
+    Ok(())
+}
+
+fn single(key: &str, mut values: Vec<String>) -> Result<String, String> {
+    match values.len() {
+        1 => Ok(values.pop().expect("impossible: one value")),
+        _ => Err(format!("{key} takes exactly one value")),
+    }
+}
+
+fn parse_word<K: FromStr>(s: &str) -> Result<K, String> {
+    K::from_str(s).map_err(|_| format!("FromStr: Cannot parse {s}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::r#struct::Orientation;
+    use super::*;
+
+    #[test]
+    fn parse_style() {
+        let input = "[Layout]\norientation = LR\nfont = \"Fira Sans\"\n\n[Labels]\nabbreviate = true\nhide = case, transitivity\n\n[Colors]\nDetP = salmon\n";
+        let style: RenderStyle<String> = input.parse().unwrap();
+        assert_eq!(style.orientation, Orientation::LeftRight);
+        assert_eq!(style.font.as_deref(), Some("Fira Sans"));
+        assert!(style.abbreviate);
+        assert!(style.hidden.contains("case"));
+        assert_eq!(style.colors.get("DetP").map(String::as_str), Some("salmon"));
+        assert_eq!(style.shape, "box");
+    }
+
+    #[test]
+    fn parse_bundled_styles() {
+        use crate::interner::GlobalKey;
+        let default = include_str!("../../../assets/styles/default.style");
+        let compact = include_str!("../../../assets/styles/compact.style");
+        assert!(default.parse::<RenderStyle<GlobalKey>>().is_ok());
+        let compact: RenderStyle<GlobalKey> = compact.parse().unwrap();
+        assert_eq!(compact.item_color, "#d5f5d5");
+        assert_eq!(compact.order.len(), 4);
+    }
+
+    #[test]
+    fn parse_style_unknown_option() {
+        let input = "[Layout]\nrotation = LR\n";
+        assert!(input.parse::<RenderStyle<String>>().is_err());
+    }
+}
@@ -0,0 +1,150 @@
+use derive_more::Display;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum Orientation {
+    #[display("TB")]
+    TopBottom,
+    #[display("BT")]
+    BottomTop,
+    #[display("LR")]
+    LeftRight,
+    #[display("RL")]
+    RightLeft,
+}
+
+impl std::str::FromStr for Orientation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "TB" => Ok(Orientation::TopBottom),
+            "BT" => Ok(Orientation::BottomTop),
+            "LR" => Ok(Orientation::LeftRight),
+            "RL" => Ok(Orientation::RightLeft),
+            _ => Err(format!(
+                "Unknown orientation {s}: expected TB, BT, LR or RL"
+            )),
+        }
+    }
+}
+
+/// how syntax values are drawn in dot graphs
+#[derive(Debug, Clone)]
+pub struct RenderStyle<K> {
+    pub orientation: Orientation,
+    pub shape: String,
+    pub font: Option<String>,
+    pub item_color: String,
+    pub feature_color: String,
+    /// node color by the first listed category that has one
+    pub colors: BTreeMap<K, String>,
+    /// categories left out of labels
+    pub hidden: BTreeSet<K>,
+    /// show `tense:past` as `past`
+    pub abbreviate: bool,
+    /// categories listed first, in this order; the rest follow alphabetically
+    pub order: Vec<K>,
+}
+
+impl<K> Default for RenderStyle<K> {
+    fn default() -> Self {
+        Self {
+            orientation: Orientation::TopBottom,
+            shape: "box".to_string(),
+            font: None,
+            item_color: "lightgreen".to_string(),
+            feature_color: "lightblue".to_string(),
+            colors: BTreeMap::new(),
+            hidden: BTreeSet::new(),
+            abbreviate: false,
+            order: Vec::new(),
+        }
+    }
+}
+
+impl<K> RenderStyle<K> {
+    /// the opening lines of a dot graph, up to and including node defaults
+    pub fn to_dot_header(&self) -> String {
+        let font = match &self.font {
+            Some(font) => format!(", fontname=\"{font}\""),
+            None => String::new(),
+        };
+        format!(
+            "digraph {{\n    rankdir={};\n    node [shape={}, style=filled{}];\n",
+            self.orientation, self.shape, font
+        )
+    }
+}
+
+impl<K: std::fmt::Display + Ord> RenderStyle<K> {
+    /// visible `(category, label)` pairs in display order
+    pub fn label_entries<'a, I>(&self, features: I) -> Vec<(&'a K, String)>
+    where
+        I: IntoIterator<Item = (&'a K, &'a Option<K>)>,
+    {
+        let mut entries = features
+            .into_iter()
+            .filter(|(category, _)| !self.hidden.contains(*category))
+            .map(|(category, value)| {
+                let label = match value {
+                    Some(value) if self.abbreviate => format!("{value}"),
+                    Some(value) => format!("{category}:{value}"),
+                    None => format!("{category}"),
+                };
+                (category, label)
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|(category, label)| {
+            let rank = self.order.iter().position(|k| k == *category);
+            (rank.unwrap_or(usize::MAX), label.clone())
+        });
+        entries
+    }
+
+    pub fn color_of<'a, I>(&self, categories: I) -> &str
+    where
+        I: IntoIterator<Item = &'a K>,
+        K: 'a,
+    {
+        categories
+            .into_iter()
+            .find_map(|category| self.colors.get(category))
+            .unwrap_or(&self.feature_color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::FeatureSet;
+
+    #[test]
+    fn label_entries_respect_style() {
+        let mut features = FeatureSet::new();
+        features.insert("DetP", None);
+        features.insert("case", Some("obj"));
+        features.insert("number", Some("sg"));
+
+        let mut style = RenderStyle::default();
+        assert_eq!(
+            style.label_entries(features.iter()),
+            vec![
+                (&"DetP", "DetP".to_string()),
+                (&"case", "case:obj".to_string()),
+                (&"number", "number:sg".to_string()),
+            ]
+        );
+
+        style.abbreviate = true;
+        style.hidden.insert("case");
+        style.order.push("number");
+        style.colors.insert("DetP", "salmon".to_string());
+        let entries = style.label_entries(features.iter());
+        assert_eq!(
+            entries,
+            vec![(&"number", "sg".to_string()), (&"DetP", "DetP".to_string())]
+        );
+        assert_eq!(style.color_of(entries.iter().map(|(k, _)| *k)), "salmon");
+    }
+}
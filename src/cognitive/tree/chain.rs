@@ -0,0 +1,140 @@
+use super::NodeID;
+use super::TreeModel;
+use super::error::Result;
+use std::collections::HashMap;
+
+/// a moved constituent together with the traces it left behind
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MovementChain {
+    /// co-index shared by the head and its traces, starting from 1
+    pub index: usize,
+    /// the overt constituent, i.e., the node every trace ultimately points to
+    pub head: NodeID,
+    /// traces ordered from the head downwards
+    pub traces: Vec<NodeID>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainRole {
+    Head(usize),
+    Trace(usize),
+}
+
+pub fn subscript(index: usize) -> String {
+    const DIGITS: [char; 10] = ['₀', '₁', '₂', '₃', '₄', '₅', '₆', '₇', '₈', '₉'];
+    index
+        .to_string()
+        .chars()
+        .map(|c| DIGITS[c.to_digit(10).expect("impossible: not a digit") as usize])
+        .collect()
+}
+
+impl<K> TreeModel<K> {
+    /// node IDs in pre-order, the tree first and then the unattached subtrees
+    fn preorder(&self) -> Result<Vec<NodeID>> {
+        let mut order = Vec::new();
+        if self.is_empty() {
+            return Ok(order);
+        }
+
+        let mut roots = vec![self.get_root()];
+        roots.extend(self.get_unattached());
+        for root in roots {
+            let mut nodes = vec![root];
+            while let Some(id) = nodes.pop() {
+                order.push(id);
+                if let Some(right_id) = self.get_right(id)? {
+                    nodes.push(right_id);
+                }
+                if let Some(left_id) = self.get_left(id)? {
+                    nodes.push(left_id);
+                }
+            }
+        }
+        Ok(order)
+    }
+
+    /// all movement chains, indexed left to right by the position of their heads
+    pub fn movement_chains(&self) -> Result<Vec<MovementChain>> {
+        let order = self.preorder()?;
+
+        let mut traces: HashMap<NodeID, Vec<(usize, NodeID)>> = HashMap::new();
+        for &id in order.iter() {
+            let mut head = id;
+            let mut depth = 0;
+            while let Some(moved_id) = self.get_moved(head)? {
+                head = moved_id;
+                depth += 1;
+            }
+            if depth > 0 {
+                traces.entry(head).or_default().push((depth, id));
+            }
+        }
+
+        let mut heads = traces.keys().copied().collect::<Vec<_>>();
+        heads.sort_by_key(|head| order.iter().position(|id| id == head));
+
+        let chains = heads
+            .into_iter()
+            .enumerate()
+            .map(|(index, head)| {
+                let mut chain_traces = traces.remove(&head).unwrap_or_default();
+                chain_traces.sort();
+                MovementChain {
+                    index: index + 1,
+                    head,
+                    traces: chain_traces.into_iter().map(|(_, id)| id).collect(),
+                }
+            })
+            .collect();
+        Ok(chains)
+    }
+
+    pub fn chain_roles(&self) -> Result<HashMap<NodeID, ChainRole>> {
+        let mut roles = HashMap::new();
+        for chain in self.movement_chains()? {
+            roles.insert(chain.head, ChainRole::Head(chain.index));
+            for trace in chain.traces {
+                roles.insert(trace, ChainRole::Trace(chain.index));
+            }
+        }
+        Ok(roles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cognitive::{LambdaModel, RenderStyle};
+    use crate::dialect::{Dialect, English};
+    use crate::interpreter::{follow, interpret};
+
+    #[test]
+    fn movement_chains_of_example() {
+        let dialect = English::default();
+        let _scope = dialect.interner().enter();
+        let sentence = "the child ate an apple in the room.";
+        let result = interpret::<_, LambdaModel<_>>(&dialect, sentence, "Sentence").unwrap();
+        let mut tree = follow::<_, TreeModel<_>>("Sentence", result[0].clone()).unwrap();
+        tree.prune().unwrap();
+
+        let chains = tree.movement_chains().unwrap();
+        assert_eq!(chains.len(), 2);
+        assert_eq!(chains[0].index, 1);
+        assert_eq!(chains[0].traces.len(), 1);
+        assert_eq!(chains[1].traces.len(), 2);
+        for chain in chains {
+            assert_eq!(tree.get_moved(chain.traces[0]).unwrap(), Some(chain.head));
+        }
+
+        let bracketed = tree.to_bracketed(&RenderStyle::default()).unwrap();
+        assert!(bracketed.contains("[DetP-case:subj-number:sg₁ [DetH-number:sg the]"));
+        assert!(bracketed.contains("[DetP-case:nocase-number:sg t₁]"));
+    }
+
+    #[test]
+    fn subscript_digits() {
+        assert_eq!(subscript(1), "₁");
+        assert_eq!(subscript(12), "₁₂");
+    }
+}
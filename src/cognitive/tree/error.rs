@@ -22,9 +22,22 @@ pub enum Error {
     #[from(ignore)]
     NodeIsNotFeatures(NodeID),
 
+    #[from(ignore)]
+    NoPendingArgument(usize),
+
+    #[display("Moved copy of node {} is inconsistent with it", _0)]
+    #[from(ignore)]
+    MovedCopyInconsistent(NodeID),
+
+    #[display("Node {} would be the whole node it is the corner of", _0)]
+    #[from(ignore)]
+    CornerIsWholeNode(NodeID),
+
     Syntax(crate::syntax::Error),
 
-    LambdaToIsNestedLambda,
+    LambdaToIsNotFeatures,
+
+    KeyDoesNotUnify,
 
     StdFmt(std::fmt::Error),
     StdIO(std::io::Error),
@@ -1,91 +1,216 @@
 use super::Error;
+use super::NodeID;
 use super::TreeModel;
-use crate::syntax::SyntaxValue;
+use super::chain::{ChainRole, subscript};
+use crate::cognitive::graph::is_dot_installed;
+use crate::cognitive::style::RenderStyle;
+use crate::syntax::{FeatureSet, SyntaxValue};
 use graphviz_rust::cmd::{CommandArg, Format};
 use graphviz_rust::exec_dot;
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::fmt::Write;
 
-impl<K: Display> SyntaxValue<K> {
-    pub fn to_dot_attr(&self) -> String {
+impl<K: Display + Ord> FeatureSet<K> {
+    pub fn to_dot_label(&self, style: &RenderStyle<K>) -> String {
+        let entries = style.label_entries(self.iter());
+        let labels = entries.into_iter().map(|(_, label)| label);
+        labels.collect::<Vec<_>>().join("\n")
+    }
+}
+
+impl<K: Display + Ord> SyntaxValue<K> {
+    pub fn to_dot_label(&self, style: &RenderStyle<K>) -> String {
+        match self {
+            SyntaxValue::Item(item) => format!("{}", item),
+            SyntaxValue::Features(features) => features.to_dot_label(style),
+        }
+    }
+
+    pub fn to_dot_color<'a>(&self, style: &'a RenderStyle<K>) -> &'a str {
         match self {
-            SyntaxValue::Item(item) => format!("label=\"{}\", color=lightgreen", item),
+            SyntaxValue::Item(_) => &style.item_color,
             SyntaxValue::Features(features) => {
-                let mut entries = Vec::new();
-                for (category, value) in features.iter() {
-                    match value {
-                        Some(value) => entries.push(format!("{}:{}", category, value)),
-                        None => entries.push(format!("{}", category)),
-                    }
-                }
-                entries.sort();
-                format!("label=\"{}\", color=lightblue", entries.join("\n"))
+                let entries = style.label_entries(features.iter());
+                style.color_of(entries.into_iter().map(|(category, _)| category))
             }
         }
     }
+
+    pub fn to_dot_attr(&self, style: &RenderStyle<K>) -> String {
+        format!(
+            "label=\"{}\", color=\"{}\"",
+            self.to_dot_label(style),
+            self.to_dot_color(style)
+        )
+    }
+}
+
+impl ChainRole {
+    /// a head carries the index on its first line, a trace shows `t` with the index on top
+    pub fn annotate_dot_label(&self, label: &str) -> String {
+        match self {
+            ChainRole::Head(index) => match label.split_once('\n') {
+                Some((first, rest)) => format!("{}{}\n{}", first, subscript(*index), rest),
+                None => format!("{}{}", label, subscript(*index)),
+            },
+            ChainRole::Trace(index) => format!("t{}\n{}", subscript(*index), label),
+        }
+    }
 }
 
-impl<K: Display> TreeModel<K> {
-    pub fn to_dot_graph(&self) -> Result<String, Error> {
+impl<K: Display + Ord> TreeModel<K> {
+    pub fn to_dot_graph(&self, style: &RenderStyle<K>) -> Result<String, Error> {
         let mut graph = String::new();
         if self.is_empty() {
             return Ok(graph);
         }
 
-        graph.push_str("digraph {{\n");
-        graph.push_str("    rankdir=TB;\n");
-        graph.push_str("    node [shape=box, style=filled];\n");
+        graph.push_str(&style.to_dot_header());
+        self.write_dot_nodes(&mut graph, "", style)?;
+        graph.push_str("}\n");
+        Ok(graph)
+    }
+
+    /// writes the tree and its unattached subtrees, with `prefix` keeping node IDs unique
+    pub fn write_dot_nodes(
+        &self,
+        graph: &mut String,
+        prefix: &str,
+        style: &RenderStyle<K>,
+    ) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let roles = self.chain_roles()?;
+        self.write_dot_subtree(graph, prefix, style, &roles, self.get_root())?;
+        for &id in self.get_unattached() {
+            self.write_dot_subtree(graph, prefix, style, &roles, id)?;
+            writeln!(graph, r#"    "{}{}" [peripheries=2];"#, prefix, id)?;
+        }
+        Ok(())
+    }
 
+    fn write_dot_subtree(
+        &self,
+        graph: &mut String,
+        prefix: &str,
+        style: &RenderStyle<K>,
+        roles: &HashMap<NodeID, ChainRole>,
+        root: NodeID,
+    ) -> Result<(), Error> {
         let mut nodes = Vec::new();
-        nodes.push(self.get_root());
+        nodes.push(root);
         while let Some(id) = nodes.pop() {
-            let value = self.get_value(id)?.to_dot_attr();
-            writeln!(&mut graph, r#"    "{}" [{}];"#, id, value)?;
+            let value = self.get_value(id)?;
+            let mut label = value.to_dot_label(style);
+            if let Some(lemma) = self.get_lemma(id)? {
+                label = format!("{label}\n({lemma})");
+            }
+            if let Some(role) = roles.get(&id) {
+                label = role.annotate_dot_label(&label);
+            }
+            let color = value.to_dot_color(style);
+            writeln!(
+                graph,
+                r#"    "{}{}" [label="{}", color="{}"];"#,
+                prefix, id, label, color
+            )?;
 
             if let Some(left_id) = self.get_left(id)? {
                 writeln!(
-                    &mut graph,
-                    r#"    "{}" -> "{}" [arrowhead=none];"#,
-                    id, left_id
+                    graph,
+                    r#"    "{}{}" -> "{}{}" [arrowhead=none];"#,
+                    prefix, id, prefix, left_id
                 )?;
                 nodes.push(left_id);
             }
             if let Some(right_id) = self.get_right(id)? {
                 writeln!(
-                    &mut graph,
-                    r#"    "{}" -> "{}" [arrowhead=none];"#,
-                    id, right_id
+                    graph,
+                    r#"    "{}{}" -> "{}{}" [arrowhead=none];"#,
+                    prefix, id, prefix, right_id
                 )?;
                 nodes.push(right_id);
             }
             if let Some(moved_id) = self.get_moved(id)? {
                 writeln!(
-                    &mut graph,
-                    r#"    "{}" -> "{}" [style=dashed, constraint=false, color=blue];"#,
-                    id, moved_id
+                    graph,
+                    r#"    "{}{}" -> "{}{}" [style=dashed, constraint=false, color=blue];"#,
+                    prefix, id, prefix, moved_id
                 )?;
             }
         }
-
-        graph.push_str("}}");
-        Ok(graph)
+        Ok(())
     }
 
-    pub fn to_png(&self, filename: String) -> Result<(), Error> {
+    pub fn to_png(&self, filename: String, style: &RenderStyle<K>) -> Result<(), Error> {
         if !is_dot_installed() {
             return Err(Error::GraphvizDotNotInstalled);
         }
-        let dot = self.to_dot_graph()?;
+        let dot = self.to_dot_graph(style)?;
 
         exec_dot(dot, vec![Format::Png.into(), CommandArg::Output(filename)])?;
         Ok(())
     }
-}
 
-use std::process::Command;
-fn is_dot_installed() -> bool {
-    match Command::new("dot").arg("-V").output() {
-        Ok(output) => output.status.success(),
-        Err(_) => false,
+    pub fn to_svg(&self, filename: String, style: &RenderStyle<K>) -> Result<(), Error> {
+        if !is_dot_installed() {
+            return Err(Error::GraphvizDotNotInstalled);
+        }
+        let dot = self.to_dot_graph(style)?;
+
+        exec_dot(dot, vec![Format::Svg.into(), CommandArg::Output(filename)])?;
+        Ok(())
+    }
+
+    /// labelled bracketing, e.g., `[DetP₁ [DetH the] [NounP child]] ... [DetP t₁]`
+    pub fn to_bracketed(&self, style: &RenderStyle<K>) -> Result<String, Error> {
+        let mut bracketed = String::new();
+        if self.is_empty() {
+            return Ok(bracketed);
+        }
+
+        let roles = self.chain_roles()?;
+        self.write_bracketed(&mut bracketed, style, &roles, self.get_root())?;
+        Ok(bracketed)
+    }
+
+    fn write_bracketed(
+        &self,
+        bracketed: &mut String,
+        style: &RenderStyle<K>,
+        roles: &HashMap<NodeID, ChainRole>,
+        id: NodeID,
+    ) -> Result<(), Error> {
+        let features = match self.get_value(id)? {
+            SyntaxValue::Item(item) => {
+                write!(bracketed, "{}", item)?;
+                if let Some(lemma) = self.get_lemma(id)? {
+                    write!(bracketed, " ({})", lemma)?;
+                }
+                return Ok(());
+            }
+            SyntaxValue::Features(features) => features,
+        };
+
+        let labels = style.label_entries(features.iter());
+        let label = labels.into_iter().map(|(_, label)| label);
+        write!(bracketed, "[{}", label.collect::<Vec<_>>().join("-"))?;
+        match roles.get(&id) {
+            Some(ChainRole::Head(index)) => write!(bracketed, "{}", subscript(*index))?,
+            Some(ChainRole::Trace(index)) => write!(bracketed, " t{}", subscript(*index))?,
+            None => (),
+        }
+        for child_id in [self.get_left(id)?, self.get_right(id)?]
+            .into_iter()
+            .flatten()
+        {
+            bracketed.push(' ');
+            self.write_bracketed(bracketed, style, roles, child_id)?;
+        }
+        bracketed.push(']');
+        Ok(())
     }
 }
@@ -1,17 +1,30 @@
 use super::NodeID;
-use crate::syntax::{FeatureSet, SyntaxValue};
+use crate::lexicon::LexiconNode;
+use crate::syntax::{Bindings, FeatureSet, SyntaxValue, Variable};
+
+/// the `to` side of a higher-order entry, e.g., `(C > D)` in `(A > (C > D))`,
+/// inserted as a parent once the node holding it is complete
+#[derive(Debug, Clone)]
+pub struct Deferred<K> {
+    pub value: LexiconNode<K>,
+    pub trigger: NodeID,
+    pub key: Option<FeatureSet<K>>,
+}
 
 #[derive(Debug, Clone)]
 pub struct Node<K> {
     // self
     id: NodeID,
     value: SyntaxValue<K>,
+    /// what a token is a form of, e.g., `eat` for `ate`
+    lemma: Option<K>,
     done: bool,
 
     // parent
     parent: Option<NodeID>,
     is_left: bool,
     project: Option<FeatureSet<K>>,
+    deferred: Option<Deferred<K>>,
 
     // children
     left: Option<NodeID>,
@@ -26,10 +39,12 @@ impl<K> Node<K> {
         Node {
             id,
             value,
+            lemma: None,
             done: false,
             parent: None,
             is_left: false,
             project: None,
+            deferred: None,
             left: None,
             right: None,
             moved: None,
@@ -41,6 +56,12 @@ impl<K> Node<K> {
     pub fn get_value(&self) -> &SyntaxValue<K> {
         &self.value
     }
+    pub fn get_lemma(&self) -> Option<&K> {
+        self.lemma.as_ref()
+    }
+    pub fn set_lemma(&mut self, lemma: Option<K>) {
+        self.lemma = lemma;
+    }
     pub fn if_done(&self) -> bool {
         self.done
     }
@@ -59,6 +80,12 @@ impl<K> Node<K> {
     pub fn set_project(&mut self, project: Option<FeatureSet<K>>) {
         self.project = project;
     }
+    pub fn take_deferred(&mut self) -> Option<Deferred<K>> {
+        self.deferred.take()
+    }
+    pub fn set_deferred(&mut self, deferred: Option<Deferred<K>>) {
+        self.deferred = deferred;
+    }
     pub fn get_parent(&self) -> Option<NodeID> {
         self.parent
     }
@@ -96,3 +123,35 @@ impl<K> Node<K> {
         self.left.is_some() as usize + self.right.is_some() as usize
     }
 }
+
+impl<K: Clone + Ord> Node<K> {
+    /// binds the variables in the value, the projection and the deferred entry
+    pub fn substitute(&mut self, bindings: &Bindings<K>) {
+        if let SyntaxValue::Features(fs) = &mut self.value {
+            fs.substitute(bindings);
+        }
+        if let Some(project) = &mut self.project {
+            project.substitute(bindings);
+        }
+        if let Some(deferred) = &mut self.deferred {
+            deferred.value.substitute(bindings);
+            if let Some(key) = &mut deferred.key {
+                key.substitute(bindings);
+            }
+        }
+    }
+
+    pub fn next_variable(&self) -> Variable {
+        let mut features = Vec::new();
+        if let SyntaxValue::Features(fs) = &self.value {
+            features.push(fs);
+        }
+        features.extend(&self.project);
+        if let Some(deferred) = &self.deferred {
+            features.extend(deferred.value.features());
+            features.extend(&deferred.key);
+        }
+        let variables = features.into_iter().map(FeatureSet::next_variable);
+        variables.max().unwrap_or(0)
+    }
+}
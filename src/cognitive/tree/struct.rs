@@ -1,9 +1,9 @@
 use super::super::CognitiveModel;
 use super::NodeID;
 use super::error::{Error, Result};
-use super::node::Node;
-use crate::lexicon::LexiconEntry;
-use crate::syntax::{FeatureSet, SyntaxValue};
+use super::node::{Deferred, Node};
+use crate::lexicon::{LexiconEntry, LexiconNode};
+use crate::syntax::{Bindings, FeatureSet, SyntaxValue, Variable};
 use std::fmt::{Debug, Display};
 
 #[derive(Clone)]
@@ -13,6 +13,10 @@ pub struct TreeModel<K> {
     upper_cursor: NodeID,
     lower_cursor: NodeID,
     unattached: Vec<NodeID>,
+    /// set when the last entry was a lambda, so that the node it expects may still take
+    /// an unattached node as its corner; holds whether the corner may be all of that node,
+    /// see `LambdaModel`
+    corner: Option<bool>,
 }
 
 // tree-level methods
@@ -24,6 +28,7 @@ impl<K> TreeModel<K> {
             upper_cursor: 0,
             lower_cursor: 0,
             unattached: Vec::new(),
+            corner: None,
         }
     }
     fn size(&self) -> usize {
@@ -35,6 +40,10 @@ impl<K> TreeModel<K> {
     pub fn get_root(&self) -> NodeID {
         self.root
     }
+    /// roots of subtrees displaced by `add_left` and not yet re-attached
+    pub fn get_unattached(&self) -> &[NodeID] {
+        &self.unattached
+    }
 }
 
 // node-level methods
@@ -59,6 +68,13 @@ impl<K> TreeModel<K> {
     fn if_done(&self, id: NodeID) -> Result<bool> {
         Ok(self.get_node(id)?.if_done())
     }
+    pub fn get_lemma(&self, id: NodeID) -> Result<Option<&K>> {
+        Ok(self.get_node(id)?.get_lemma())
+    }
+    fn set_lemma(&mut self, id: NodeID, lemma: Option<K>) -> Result<()> {
+        self.get_node_mut(id)?.set_lemma(lemma);
+        Ok(())
+    }
     fn set_done(&mut self, id: NodeID) -> Result<()> {
         self.get_node_mut(id)?.set_done();
         Ok(())
@@ -81,6 +97,13 @@ impl<K> TreeModel<K> {
         self.get_node_mut(id)?.set_project(project);
         Ok(())
     }
+    fn take_deferred(&mut self, id: NodeID) -> Result<Option<Deferred<K>>> {
+        Ok(self.get_node_mut(id)?.take_deferred())
+    }
+    fn set_deferred(&mut self, id: NodeID, deferred: Option<Deferred<K>>) -> Result<()> {
+        self.get_node_mut(id)?.set_deferred(deferred);
+        Ok(())
+    }
 
     // node children
     pub fn get_left(&self, id: NodeID) -> Result<Option<NodeID>> {
@@ -98,6 +121,14 @@ impl<K> TreeModel<K> {
         self.get_node_mut(from)?.set_moved(to);
         Ok(())
     }
+    /// traces pointing at `from` point at `to` instead
+    fn redirect_moved(&mut self, from: NodeID, to: NodeID) {
+        for node in self.nodes.iter_mut() {
+            if node.get_moved() == Some(from) {
+                node.set_moved(to);
+            }
+        }
+    }
 
     fn set_relation_left(&mut self, parent_id: NodeID, child_id: NodeID) -> Result<()> {
         let parent = self.get_node_mut(parent_id)?;
@@ -174,6 +205,32 @@ impl<K> TreeModel<K> {
         Ok(())
     }
 
+    /// pending arguments are the lower cursor followed by the unattached nodes, latest first;
+    /// bring the one at `index` to the lower cursor and stash the lower cursor as the latest
+    fn swap_pending(&mut self, index: usize) -> Result<()> {
+        if self.lower_cursor == self.upper_cursor {
+            return Err(Error::NoPendingArgument(index));
+        }
+        let pending_id = self.take_unattached(index)?;
+        let cur_id = self.lower_cursor;
+
+        let parent_id = self.get_parent(cur_id)?;
+        let cur_is_left = self.get_is_left(cur_id)?;
+        self.delete_relation(parent_id, cur_id)?;
+        self.set_relation(parent_id, pending_id, cur_is_left)?;
+        self.unattached.push(cur_id);
+        self.lower_cursor = pending_id;
+        Ok(())
+    }
+
+    /// the unattached node at `index` of the pending arguments
+    fn take_unattached(&mut self, index: usize) -> Result<NodeID> {
+        match self.unattached.len().checked_sub(index) {
+            Some(position) if index > 0 => Ok(self.unattached.remove(position)),
+            _ => Err(Error::NoPendingArgument(index)),
+        }
+    }
+
     fn add_child(&mut self, child_id: NodeID, is_left: bool) -> Result<()> {
         match is_left {
             true => self.add_left(child_id),
@@ -206,6 +263,19 @@ impl<K: Ord + Clone> TreeModel<K> {
         FeatureSet::project(&from_fs, onto_fs, ignore)?;
         Ok(())
     }
+
+    /// binds the variables in every node
+    fn substitute(&mut self, bindings: &Bindings<K>) {
+        for node in self.nodes.iter_mut() {
+            node.substitute(bindings);
+        }
+    }
+
+    /// the smallest variable not in any node, to rename the variables of an entry apart
+    fn next_variable(&self) -> Variable {
+        let variables = self.nodes.iter().map(Node::next_variable);
+        variables.max().unwrap_or(0)
+    }
 }
 
 // interface with Lexicon
@@ -214,14 +284,26 @@ mod lexicon {
     use crate::lexicon::LexiconNode;
 
     impl<K: Ord + Clone> TreeModel<K> {
+        /// `key` is the features a functional entry was looked up by
         pub fn insert_parent(
             &mut self,
             value: LexiconNode<K>,
             with_project: Option<FeatureSet<K>>,
+            key: Option<&FeatureSet<K>>,
+        ) -> Result<()> {
+            self.insert_parent_for(value, with_project, self.lower_cursor, key)
+        }
+
+        /// like `insert_parent`, with MOVED() copies pointing at `trigger`
+        fn insert_parent_for(
+            &mut self,
+            value: LexiconNode<K>,
+            with_project: Option<FeatureSet<K>>,
+            trigger: NodeID,
+            key: Option<&FeatureSet<K>>,
         ) -> Result<()> {
             let cur_id = self.lower_cursor;
             let cur_is_left = self.get_is_left(cur_id)?;
-            let old_upper_cursor = self.upper_cursor;
 
             // detach cur from its parent
             let parent_id = self.get_parent(cur_id)?;
@@ -232,7 +314,7 @@ mod lexicon {
             self.lower_cursor = parent_id;
 
             // append new parent as child of old parent
-            let new_parent_id = self.append_child(value, cur_id, cur_is_left)?;
+            let new_parent_id = self.append_child(value, trigger, cur_is_left, key)?;
 
             // link cur and new parent
             self.set_relation(new_parent_id, cur_id, cur_is_left)?;
@@ -243,13 +325,6 @@ mod lexicon {
                 self.project(cur_id, &ignore)?;
             }
 
-            // re-add unattached nodes after the tree has been adjusted
-            if old_upper_cursor != self.upper_cursor {
-                if let Some(unattached_id) = self.unattached.pop() {
-                    self.add_left(unattached_id)?
-                }
-            }
-
             // start from lower_cursor
             // if satisfies parent's features, go upward
             self.try_project()
@@ -260,6 +335,7 @@ mod lexicon {
             value: LexiconNode<K>,
             trigger: NodeID,
             child_is_left: bool,
+            key: Option<&FeatureSet<K>>,
         ) -> Result<NodeID> {
             match value {
                 LexiconNode::Value { value } => {
@@ -272,21 +348,44 @@ mod lexicon {
                     to,
                     project: from_project,
                 } => {
-                    let to = match *to {
-                        LexiconNode::Value { value } => Ok(value),
-                        _ => Err(Error::LambdaToIsNestedLambda),
-                    }?;
+                    // a nested `to` is labelled by its final features until it is inserted
+                    let (to, deferred) = match *to {
+                        LexiconNode::Value { value } => (value, None),
+                        to @ LexiconNode::Lambda { .. } => {
+                            let value = match to.get_features_right() {
+                                Some(fs) => SyntaxValue::from(fs.clone()),
+                                None => Err(Error::LambdaToIsNotFeatures)?,
+                            };
+                            let key = key.cloned();
+                            (
+                                value,
+                                Some(Deferred {
+                                    value: to,
+                                    trigger,
+                                    key,
+                                }),
+                            )
+                        }
+                        LexiconNode::Moved { .. } => Err(Error::LambdaToIsNotFeatures)?,
+                    };
                     let child_id = self.new_node(to);
+                    self.set_deferred(child_id, deferred)?;
                     self.add_child(child_id, child_is_left)?;
 
-                    let child_child_id = self.append_child(*from, trigger, !child_is_left)?;
+                    let child_child_id = self.append_child(*from, trigger, !child_is_left, key)?;
                     if from_project {
                         let ignore_fs = self.get_features(child_child_id)?;
                         self.set_project(child_child_id, Some(ignore_fs.clone()))?;
                     }
                     Ok(child_id)
                 }
-                LexiconNode::Moved { from } => {
+                LexiconNode::Moved { mut from } => {
+                    // the copy carries the trigger's features, see `ValidEntry::project_onto_moved`
+                    if let Some(key) = key {
+                        let trigger_fs = self.get_features(trigger)?;
+                        FeatureSet::project(trigger_fs, &mut from, key)
+                            .map_err(|_| Error::MovedCopyInconsistent(trigger))?;
+                    }
                     let from = SyntaxValue::from(from);
                     let child_id = self.new_node(from);
                     self.add_child(child_id, child_is_left)?;
@@ -296,19 +395,57 @@ mod lexicon {
             }
         }
 
+        /// the unattached node at `index` of the pending arguments becomes the left corner
+        /// of the expected node, or comes next if the expected node has a left corner already
+        pub fn take_corner(&mut self, index: usize, whole: bool) -> Result<()> {
+            let pending_id = self.take_unattached(index)?;
+            // unless it may be all of it, the corner is the leftmost part of the expected node,
+            // which takes what follows
+            let expected_fs = self.get_features(self.upper_cursor)?;
+            let pending_fs = self.get_features(pending_id)?;
+            if !whole && expected_fs.unify(pending_fs, &mut Bindings::new()) {
+                return Err(Error::CornerIsWholeNode(pending_id));
+            }
+            match self.lower_cursor == self.upper_cursor {
+                true => {
+                    self.add_left(pending_id)?;
+                    self.try_project()
+                }
+                false => {
+                    self.unattached.push(pending_id);
+                    Ok(())
+                }
+            }
+        }
+
         fn try_project(&mut self) -> Result<()> {
             let cur_id = self.lower_cursor;
 
+            // the node was built from its left corner and what it took, now it takes the rest
+            if let Some(deferred) = self.take_deferred(cur_id)? {
+                self.upper_cursor = self.get_parent(cur_id)?;
+                let key = deferred.key.as_ref();
+                return self.insert_parent_for(deferred.value, None, deferred.trigger, key);
+            }
+
             let parent_id = match self.get_parent(cur_id) {
                 Ok(id) => id,
                 Err(_) => return Ok(()),
             };
 
+            // a re-attached token waits for its lexical entry
+            if let SyntaxValue::Item(_) = self.get_value(cur_id)? {
+                return Ok(());
+            }
+
             if !self.if_done(cur_id)? && self.get_is_left(cur_id)? {
                 let cur_fs = self.get_features(cur_id)?;
                 let parent_fs = self.get_features(parent_id)?;
-                if parent_fs.is_subset(cur_fs) {
-                    self.project(cur_id, &parent_fs.clone())?;
+                let mut bindings = Bindings::new();
+                if parent_fs.unify(cur_fs, &mut bindings) {
+                    self.substitute(&bindings);
+                    let parent_fs = self.get_features(parent_id)?.clone();
+                    self.project(cur_id, &parent_fs)?;
                     self.set_done(cur_id)?;
                     self.set_done(parent_id)?;
                 }
@@ -345,6 +482,7 @@ impl<K: Ord + Clone> CognitiveModel<K> for TreeModel<K> {
         true
     }
     fn receive(&mut self, token: K) -> super::super::error::Result<()> {
+        self.corner = None;
         let new_node = SyntaxValue::from(token);
         let new_node = self.new_node(new_node);
         self.add_left(new_node)?;
@@ -353,10 +491,48 @@ impl<K: Ord + Clone> CognitiveModel<K> for TreeModel<K> {
     fn wonder(&self) -> Option<&SyntaxValue<K>> {
         self.get_value(self.lower_cursor).ok()
     }
-    fn decide(&mut self, entry: LexiconEntry<K>) -> super::super::error::Result<()> {
+    fn moved(&self) -> bool {
+        matches!(self.get_moved(self.lower_cursor), Ok(Some(_)))
+    }
+    fn pending(&self) -> usize {
+        match self.corner.is_some() || self.lower_cursor != self.upper_cursor {
+            true => 1 + self.unattached.len(),
+            false => 1,
+        }
+    }
+    fn reorder(&mut self, index: usize) -> super::super::error::Result<()> {
+        match self.corner.take() {
+            Some(whole) => self.take_corner(index, whole)?,
+            None => self.swap_pending(index)?,
+        }
+        Ok(())
+    }
+    fn decide(&mut self, mut entry: LexiconEntry<K>) -> super::super::error::Result<()> {
+        entry.offset_variables(self.next_variable());
+        // the key binds the variables in hand, and the features in hand those of the entry
+        if let Ok(query) = self.get_features(self.lower_cursor) {
+            let bindings = entry.bind(query).ok_or(Error::KeyDoesNotUnify)?;
+            self.substitute(&bindings);
+        }
+        let lambda = matches!(
+            &entry,
+            LexiconEntry::Lexical(LexiconNode::Lambda { .. }, _)
+                | LexiconEntry::Guess(LexiconNode::Lambda { .. })
+                | LexiconEntry::Functional {
+                    to: LexiconNode::Lambda { .. },
+                    ..
+                }
+        );
+        self.corner = lambda.then(|| self.moved());
         match entry {
-            LexiconEntry::Lexical(value) => self.insert_parent(value, None)?,
-            LexiconEntry::Functional { to, project } => self.insert_parent(to, project)?,
+            LexiconEntry::Lexical(value, lemma) => {
+                self.set_lemma(self.lower_cursor, lemma)?;
+                self.insert_parent(value, None, None)?
+            }
+            LexiconEntry::Guess(value) => self.insert_parent(value, None, None)?,
+            LexiconEntry::Functional { to, project, key } => {
+                self.insert_parent(to, project, Some(&key))?
+            }
         };
         Ok(())
     }
@@ -484,6 +660,7 @@ impl<K: Clone + Ord> TreeModel<K> {
             let right_id = node.get_right();
             if delete {
                 self.delete_relation(parent_id, id)?;
+                self.redirect_moved(id, parent_id);
                 if let Some(left_id) = left_id {
                     self.delete_relation(id, left_id)?;
                     self.set_relation_left(parent_id, left_id)?;
@@ -511,3 +688,45 @@ impl<K: Clone + Ord> TreeModel<K> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cognitive::RenderStyle;
+    use crate::lexicon::LexiconNode;
+
+    fn node(category: &'static str) -> LexiconNode<&'static str> {
+        LexiconNode::Value {
+            value: SyntaxValue::Features(FeatureSet::from_category(category)),
+        }
+    }
+
+    fn lambda(
+        from: LexiconNode<&'static str>,
+        to: LexiconNode<&'static str>,
+    ) -> LexiconNode<&'static str> {
+        LexiconNode::Lambda {
+            from: Box::new(from),
+            to: Box::new(to),
+            project: false,
+        }
+    }
+
+    #[test]
+    fn decide_nested_to() {
+        let mut model = TreeModel::init(FeatureSet::from_category("D"));
+        // k = (A > (C > D)), i.e., k takes A and then C to become D
+        let entries = [
+            ("k", lambda(node("A"), lambda(node("C"), node("D")))),
+            ("a", node("A")),
+            ("c", node("C")),
+        ];
+        for (token, entry) in entries {
+            model.receive(token).unwrap();
+            model.decide(LexiconEntry::Lexical(entry, None)).unwrap();
+        }
+        assert!(model.understood());
+        let bracketed = model.to_bracketed(&RenderStyle::default()).unwrap();
+        assert_eq!(bracketed, "[D [D [D k [A [A a]]] [C [C c]]]]");
+    }
+}
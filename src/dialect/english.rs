@@ -1,8 +1,9 @@
-use super::Dialect;
-use crate::interner::GlobalKey;
-use crate::lexicon::SimpleLexicon;
+use super::{Dialect, Unknown};
+use crate::interner::{GlobalKey, Interner};
 use crate::lexicon::parser::{LexiconParser, PestLexiconParser};
+use crate::lexicon::{self, CompileError, SimpleLexicon};
 use crate::tokenizer::SimpleTokenizer;
+use log::warn;
 use std::fmt::Display;
 
 #[derive(Debug)]
@@ -10,6 +11,8 @@ pub struct English {
     name: String,
     lexicon: SimpleLexicon<GlobalKey>,
     tokenizer: SimpleTokenizer,
+    unknown: Unknown,
+    interner: Interner,
 }
 
 impl Dialect for English {
@@ -24,6 +27,12 @@ impl Dialect for English {
     fn tokenizer(&self) -> &impl crate::tokenizer::Tokenizer<Self::Token> {
         &self.tokenizer
     }
+    fn unknown(&self) -> Unknown {
+        self.unknown
+    }
+    fn interner(&self) -> &Interner {
+        &self.interner
+    }
 }
 
 impl English {
@@ -32,14 +41,35 @@ impl English {
             name: "English".to_string(),
             lexicon: SimpleLexicon::new(),
             tokenizer: SimpleTokenizer,
+            unknown: Unknown::default(),
+            interner: Interner::new(),
         }
     }
     pub fn init() -> Self {
         let mut dialect = Self::new();
+        let scope = dialect.interner.enter();
         let lexicon_str = include_str!("../../assets/lexicons/en.lexicon");
-        PestLexiconParser::parse_str(&mut dialect.lexicon, lexicon_str).unwrap();
+        let warnings = PestLexiconParser::parse_str(&mut dialect.lexicon, lexicon_str).unwrap();
+        for warning in warnings {
+            warn!("en.lexicon:{warning}");
+        }
+        drop(scope);
+        // the lexicon has every symbol of the dialect, and other words are not interned
+        dialect.interner = dialect.interner.freeze();
         dialect
     }
+    /// English with a lexicon compiled by `lexicon compile`, loaded without parsing it
+    pub fn open(path: &str) -> Result<Self, CompileError> {
+        let mut dialect = Self::new();
+        let scope = dialect.interner.enter();
+        dialect.lexicon = lexicon::open(path)?;
+        drop(scope);
+        dialect.interner = dialect.interner.freeze();
+        Ok(dialect)
+    }
+    pub fn set_unknown(&mut self, unknown: Unknown) {
+        self.unknown = unknown;
+    }
 }
 
 impl Default for English {
@@ -0,0 +1,62 @@
+use super::{Dialect, Unknown};
+use crate::interner::{GlobalKey, Interner};
+use crate::lexicon::SimpleLexicon;
+use crate::lexicon::parser::{Diagnostic, LexiconParser, PestLexiconParser};
+use crate::tokenizer::SimpleTokenizer;
+
+/// a dialect whose lexicon is loaded at run time, e.g., a version of a lexicon being edited
+#[derive(Debug)]
+pub struct FileDialect {
+    name: String,
+    lexicon: SimpleLexicon<GlobalKey>,
+    tokenizer: SimpleTokenizer,
+    unknown: Unknown,
+    interner: Interner,
+}
+
+impl Dialect for FileDialect {
+    type Token = GlobalKey;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn lexicon(&self) -> &impl crate::lexicon::Lexicon<Self::Token> {
+        &self.lexicon
+    }
+    fn tokenizer(&self) -> &impl crate::tokenizer::Tokenizer<Self::Token> {
+        &self.tokenizer
+    }
+    fn unknown(&self) -> Unknown {
+        self.unknown
+    }
+    fn interner(&self) -> &Interner {
+        &self.interner
+    }
+}
+
+impl FileDialect {
+    pub fn load(name: &str, input: &str) -> Result<Self, Vec<Diagnostic>> {
+        let mut dialect = Self {
+            name: name.to_string(),
+            ..Self::default()
+        };
+        let scope = dialect.interner.enter();
+        PestLexiconParser::parse_str(&mut dialect.lexicon, input)?;
+        drop(scope);
+        // the lexicon has every symbol of the dialect, and other words are not interned
+        dialect.interner = dialect.interner.freeze();
+        Ok(dialect)
+    }
+}
+
+impl Default for FileDialect {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            lexicon: SimpleLexicon::new(),
+            tokenizer: SimpleTokenizer,
+            unknown: Unknown::default(),
+            interner: Interner::new(),
+        }
+    }
+}
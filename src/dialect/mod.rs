@@ -1,5 +1,9 @@
 mod english;
+mod file;
 mod r#trait;
+mod unknown;
 
 pub use english::English;
+pub use file::FileDialect;
 pub use r#trait::Dialect;
+pub use unknown::Unknown;
@@ -1,3 +1,5 @@
+use super::Unknown;
+use crate::interner::Interner;
 use crate::lexicon::Lexicon;
 use crate::tokenizer::Tokenizer;
 
@@ -7,4 +9,7 @@ pub trait Dialect: Default {
     fn name(&self) -> &str;
     fn lexicon(&self) -> &impl Lexicon<Self::Token>;
     fn tokenizer(&self) -> &impl Tokenizer<Self::Token>;
+    fn unknown(&self) -> Unknown;
+    /// the interner the tokens of the dialect are interned by
+    fn interner(&self) -> &Interner;
 }
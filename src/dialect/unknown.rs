@@ -0,0 +1,26 @@
+/// what to do with a token that is not in the lexicon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Unknown {
+    /// stop with an unknown word error
+    Fail,
+    /// stop with an unknown word error naming the closest word in the lexicon
+    #[default]
+    Suggest,
+    /// try each node in `[Unknown]`, marking the interpretations as guessed
+    Guess,
+}
+
+impl std::str::FromStr for Unknown {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fail" => Ok(Unknown::Fail),
+            "suggest" => Ok(Unknown::Suggest),
+            "guess" => Ok(Unknown::Guess),
+            _ => Err(format!(
+                "Unknown strategy {s}: expected fail, suggest or guess"
+            )),
+        }
+    }
+}
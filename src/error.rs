@@ -4,6 +4,13 @@ use derive_more::{Display, From};
 pub enum Error {
     Interpreter(crate::interpreter::Error),
     Cognitive(crate::cognitive::Error),
+    Lsp(crate::lsp::Error),
+    Compile(crate::lexicon::CompileError),
+    StdIO(std::io::Error),
+    #[from(ignore)]
+    Parse(String),
+    #[from(ignore)]
+    Usage(String),
 }
 pub type Result<T> = std::result::Result<T, Error>;
 
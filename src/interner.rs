@@ -1,25 +1,155 @@
-use lasso::{Spur, ThreadedRodeo};
-use std::sync::{LazyLock, Mutex};
+use lasso::{Key, Rodeo, RodeoReader, Spur, ThreadedRodeo};
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, LazyLock};
 
-static GLOBAL_INTERNER: LazyLock<Mutex<ThreadedRodeo>> =
-    LazyLock::new(|| Mutex::new(ThreadedRodeo::new()));
+/// the interner in scope on a thread where none is entered
+static DEFAULT_INTERNER: LazyLock<Interner> = LazyLock::new(Interner::new);
+static NEXT_SCOPE: AtomicU32 = AtomicU32::new(0);
 
-#[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
-pub struct GlobalKey(Spur);
+thread_local! {
+    /// the interners entered on this thread, the innermost last
+    static SCOPES: RefCell<Vec<Interner>> = const { RefCell::new(Vec::new()) };
+}
+
+/// a handle to a table of symbols; keys of one interner are never equal to, nor resolved
+/// by, another, so that dialects or tests with their own do not share symbols
+#[derive(Debug, Clone)]
+pub struct Interner {
+    scope: u32,
+    table: Arc<Table>,
+}
+
+#[derive(Debug)]
+enum Table {
+    Open(ThreadedRodeo),
+    /// read without locking, once nothing is interned anymore
+    Frozen(RodeoReader),
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self {
+            scope: NEXT_SCOPE.fetch_add(1, Ordering::Relaxed),
+            table: Arc::new(Table::Open(ThreadedRodeo::new())),
+        }
+    }
+
+    /// makes this interner the current one on this thread, until the scope is dropped
+    pub fn enter(&self) -> Scope {
+        let depth = SCOPES.with_borrow_mut(|scopes| {
+            scopes.push(self.clone());
+            scopes.len() - 1
+        });
+        Scope {
+            depth,
+            thread: PhantomData,
+        }
+    }
+
+    /// the key of `token`, interned unless the interner is frozen; a frozen interner keeps a
+    /// token it has no symbol for as an unknown key instead
+    pub fn intern(&self, token: &str) -> GlobalKey {
+        let spur = match self.table.as_ref() {
+            Table::Open(rodeo) => Some(rodeo.get_or_intern(token)),
+            Table::Frozen(reader) => reader.get(token),
+        };
+        match spur {
+            Some(spur) => GlobalKey::Known {
+                scope: self.scope,
+                spur,
+            },
+            None => GlobalKey::Unknown(token.to_string()),
+        }
+    }
+
+    pub fn resolve<'a>(&'a self, key: &'a GlobalKey) -> Option<&'a str> {
+        match key {
+            GlobalKey::Known { scope, spur } if *scope == self.scope => match self.table.as_ref() {
+                Table::Open(rodeo) => rodeo.try_resolve(spur),
+                Table::Frozen(reader) => reader.try_resolve(spur),
+            },
+            GlobalKey::Known { .. } => None,
+            GlobalKey::Unknown(token) => Some(token),
+        }
+    }
+
+    /// the interner read-only, with the same symbols; handles still sharing the table keep
+    /// it open, and the symbols are copied for this one
+    pub fn freeze(self) -> Self {
+        let reader = match Arc::try_unwrap(self.table) {
+            Ok(Table::Open(rodeo)) => rodeo.into_reader(),
+            Ok(Table::Frozen(reader)) => reader,
+            Err(table) => {
+                let Table::Open(rodeo) = table.as_ref() else {
+                    return Self {
+                        scope: self.scope,
+                        table,
+                    };
+                };
+                // interned in the order of their keys, the symbols keep their keys
+                let mut symbols = rodeo.iter().collect::<Vec<_>>();
+                symbols.sort();
+                let mut copy = Rodeo::new();
+                for (_, token) in symbols {
+                    copy.get_or_intern(token);
+                }
+                copy.into_reader()
+            }
+        };
+        Self {
+            scope: self.scope,
+            table: Arc::new(Table::Frozen(reader)),
+        }
+    }
+}
+
+impl Default for Interner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-fn global_get(token: &str) -> GlobalKey {
-    let interner = GLOBAL_INTERNER.lock().unwrap();
-    GlobalKey(interner.get_or_intern(token))
+/// the interner entered by `Interner::enter`, current until this is dropped on its thread
+pub struct Scope {
+    /// how many interners were entered before it
+    depth: usize,
+    thread: PhantomData<*const ()>,
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        // those entered after it end with it, even if they are dropped later
+        SCOPES.with_borrow_mut(|scopes| scopes.truncate(self.depth));
+    }
 }
 
-fn global_resolve(token: &GlobalKey) -> Option<String> {
-    let interner = GLOBAL_INTERNER.lock().unwrap();
-    interner.try_resolve(&token.0).map(|s| s.to_string())
+fn with_current<T>(f: impl FnOnce(&Interner) -> T) -> T {
+    SCOPES.with_borrow(|scopes| f(scopes.last().unwrap_or(&DEFAULT_INTERNER)))
+}
+
+/// a symbol of the interner current when it was parsed
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+pub enum GlobalKey {
+    Known {
+        scope: u32,
+        spur: Spur,
+    },
+    /// a token the interner was frozen without, e.g., a word not in the lexicon
+    Unknown(String),
 }
 
 impl std::fmt::Display for GlobalKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", global_resolve(self).ok_or(std::fmt::Error)?)
+        with_current(|interner| match (interner.resolve(self), self) {
+            (Some(token), _) => f.write_str(token),
+            // a key of another interner is shown by its scope and index, rather than failing
+            (None, GlobalKey::Known { scope, spur }) => {
+                write!(f, "<{scope}:{}>", spur.into_usize())
+            }
+            (None, GlobalKey::Unknown(token)) => f.write_str(token),
+        })
     }
 }
 
@@ -27,8 +157,72 @@ impl std::str::FromStr for GlobalKey {
     type Err = std::fmt::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(global_get(s))
+        Ok(with_current(|interner| interner.intern(s)))
     }
 }
 
-// impl KeyType for GlobalKey {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn scoped_keys() {
+        let (a, b) = (Interner::new(), Interner::new());
+        let key_a = {
+            let _scope = a.enter();
+            GlobalKey::from_str("child").unwrap()
+        };
+        let key_b = {
+            let _scope = b.enter();
+            let key_b = GlobalKey::from_str("child").unwrap();
+            assert_eq!(key_b.to_string(), "child");
+            key_b
+        };
+        assert_ne!(key_a, key_b);
+        assert_eq!(a.resolve(&key_a), Some("child"));
+        assert_eq!(a.resolve(&key_b), None);
+
+        // a key is resolved by its own interner only
+        let _scope = a.enter();
+        assert_eq!(key_a.to_string(), "child");
+        assert_eq!(key_b.to_string(), format!("<{}:0>", b.scope));
+    }
+
+    #[test]
+    fn frozen_keys() {
+        let interner = Interner::new();
+        let child = {
+            let _scope = interner.enter();
+            GlobalKey::from_str("child").unwrap()
+        };
+        // the interner is still shared, and stays open
+        let frozen = interner.clone().freeze();
+        let _scope = frozen.enter();
+        assert_eq!(GlobalKey::from_str("child").unwrap(), child);
+        assert_eq!(child.to_string(), "child");
+
+        // an unseen token is not interned, and is displayed as it is spelled
+        let chlid = GlobalKey::from_str("chlid").unwrap();
+        assert_eq!(chlid, GlobalKey::Unknown("chlid".to_string()));
+        assert_eq!(chlid.to_string(), "chlid");
+        assert_eq!(frozen.intern("chlid"), chlid);
+        assert_ne!(interner.intern("chlid"), chlid);
+    }
+
+    #[test]
+    fn scopes_dropped_out_of_order() {
+        let (a, b) = (Interner::new(), Interner::new());
+        let outer = a.enter();
+        let inner = b.enter();
+        drop(outer);
+        // `b` was entered within `a`, so it is not left current on its own
+        assert_eq!(SCOPES.with_borrow(Vec::len), 0);
+        drop(inner);
+        assert_eq!(SCOPES.with_borrow(Vec::len), 0);
+
+        let _scope = a.enter();
+        let child = GlobalKey::from_str("child").unwrap();
+        assert_eq!(a.resolve(&child), Some("child"));
+    }
+}
@@ -1,10 +1,19 @@
 use crate::lexicon::LexiconEntry;
 use std::fmt::Display;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Action<K: Clone> {
     AddToken(K),
-    ApplyEntry(LexiconEntry<K>),
+    ApplyEntry(Box<LexiconEntry<K>>),
+    /// bring a pending argument forward, see `CognitiveModel::reorder`
+    Reorder(usize),
+}
+
+impl<K: Clone> Action<K> {
+    /// applies an entry guessed for a token not in the lexicon
+    pub fn is_guess(&self) -> bool {
+        matches!(self, Action::ApplyEntry(entry) if matches!(**entry, LexiconEntry::Guess(_)))
+    }
 }
 
 impl<K: Display + Clone> Display for Action<K> {
@@ -12,6 +21,7 @@ impl<K: Display + Clone> Display for Action<K> {
         match self {
             Action::AddToken(node) => write!(f, "Add token [{}]", node),
             Action::ApplyEntry(entry) => write!(f, "Apply entry [{}]", entry),
+            Action::Reorder(index) => write!(f, "Reorder argument [{}]", index),
         }
     }
 }
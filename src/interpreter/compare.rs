@@ -0,0 +1,97 @@
+use super::error::{Error, Result};
+use super::interpret::interpret;
+use crate::cognitive::CognitiveModel;
+use crate::dialect::Dialect;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::str::FromStr;
+
+/// the interpretations of a sentence in one dialect, against those in another; as the keys
+/// of two dialects are never equal, interpretations are compared by their actions as written
+#[derive(Debug)]
+pub struct Comparison {
+    pub before: usize,
+    pub after: usize,
+    /// the interpretations found before only, then after only
+    pub lost: Vec<Vec<String>>,
+    pub gained: Vec<Vec<String>>,
+}
+
+impl Comparison {
+    pub fn is_same(&self) -> bool {
+        self.lost.is_empty() && self.gained.is_empty()
+    }
+}
+
+/// interprets `text` in both dialects, e.g., two versions of a lexicon
+pub fn compare<D, C>(before: &D, after: &D, text: &str, target: &str) -> Result<Comparison>
+where
+    D: Dialect,
+    D::Token: FromStr + Clone + Ord + Hash + Display,
+    C: CognitiveModel<D::Token> + Display,
+{
+    let old = interpretations::<D, C>(before, text, target)?;
+    let new = interpretations::<D, C>(after, text, target)?;
+    let lost = old.iter().filter(|actions| !new.contains(actions));
+    let gained = new.iter().filter(|actions| !old.contains(actions));
+    Ok(Comparison {
+        before: old.len(),
+        after: new.len(),
+        lost: lost.cloned().collect(),
+        gained: gained.cloned().collect(),
+    })
+}
+
+/// the actions of each interpretation, written by the interner of the dialect; a word one
+/// version does not know has no interpretations in it, rather than being an error
+fn interpretations<D, C>(dialect: &D, text: &str, target: &str) -> Result<Vec<Vec<String>>>
+where
+    D: Dialect,
+    D::Token: FromStr + Clone + Ord + Hash + Display,
+    C: CognitiveModel<D::Token> + Display,
+{
+    let interpretations = match interpret::<D, C>(dialect, text, target) {
+        Err(Error::UnknownWord(_) | Error::MisspelledWord(..)) => return Ok(Vec::new()),
+        result => result?,
+    };
+    let _scope = dialect.interner().enter();
+    let written = interpretations.iter().map(|actions| {
+        let actions = actions.iter().map(|action| action.to_string());
+        actions.collect::<Vec<_>>()
+    });
+    Ok(written.collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cognitive::LambdaModel;
+    use crate::dialect::FileDialect;
+
+    #[test]
+    fn compare_lexicon_versions() {
+        let before = "[Functional]
+DetH = (NounP > DetP)
+DetP = (VerbP > Sentence)
+[Lexical]
+the = DetH
+dogs = NounP
+run = VerbP
+";
+        // `run` may also be a noun, as in "the run", which the sentence does not allow
+        let after = format!("{before}run = NounP\ndogs = VerbP\n");
+        let before = FileDialect::load("before", before).unwrap();
+        let after = FileDialect::load("after", &after).unwrap();
+
+        let comparison =
+            compare::<_, LambdaModel<_>>(&before, &after, "the dogs run", "Sentence").unwrap();
+        assert!(comparison.is_same());
+        assert_eq!((comparison.before, comparison.after), (1, 1));
+
+        let comparison =
+            compare::<_, LambdaModel<_>>(&before, &after, "the run dogs", "Sentence").unwrap();
+        assert_eq!((comparison.before, comparison.after), (0, 1));
+        assert_eq!(comparison.gained.len(), 1);
+        assert!(comparison.lost.is_empty());
+    }
+}
@@ -5,7 +5,16 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug, Display, From)]
 pub enum Error {
     FromStr,
+    #[display("Unknown word {}", _0)]
+    UnknownWord(String),
+    #[display("Unknown word {}, did you mean {}?", _0, _1)]
+    MisspelledWord(String, String),
     Cognitive(crate::cognitive::Error),
+
+    StdFmt(std::fmt::Error),
+    StdIO(std::io::Error),
+
+    GraphvizDotNotInstalled,
 }
 
 impl std::error::Error for Error {}
@@ -18,10 +18,36 @@ pub fn follow<K: Clone + FromStr + Ord + Debug, C: CognitiveModel<K> + Debug>(
         debug!("{action:?}");
         match action {
             Action::AddToken(token) => cogmodel.receive(token)?,
-            Action::ApplyEntry(entry) => cogmodel.decide(entry)?,
+            Action::ApplyEntry(entry) => cogmodel.decide(*entry)?,
+            Action::Reorder(index) => cogmodel.reorder(index)?,
         }
         debug!("{cogmodel:?}");
     }
 
     Ok(cogmodel)
 }
+
+/// like `follow`, but keeps the cognitive model after every action prefix,
+/// starting with the freshly initialized one
+pub fn follow_steps<K: Clone + FromStr + Ord + Debug, C: CognitiveModel<K> + Debug>(
+    target: &str,
+    actions: Vec<Action<K>>,
+) -> Result<Vec<C>> {
+    let target = K::from_str(target).map_err(|_| Error::FromStr)?;
+    let target = FeatureSet::from_category(target);
+    let mut cogmodel = C::init(target);
+    let mut steps = vec![cogmodel.clone()];
+
+    for action in actions {
+        debug!("{action:?}");
+        match action {
+            Action::AddToken(token) => cogmodel.receive(token)?,
+            Action::ApplyEntry(entry) => cogmodel.decide(*entry)?,
+            Action::Reorder(index) => cogmodel.reorder(index)?,
+        }
+        debug!("{cogmodel:?}");
+        steps.push(cogmodel.clone());
+    }
+
+    Ok(steps)
+}
@@ -0,0 +1,157 @@
+use super::action::Action;
+use super::error::{Error, Result};
+use crate::cognitive::{RenderStyle, is_dot_installed};
+use graphviz_rust::cmd::{CommandArg, Format};
+use graphviz_rust::exec_dot;
+use std::collections::HashMap;
+use std::fmt::{Display, Write};
+use std::hash::Hash;
+
+/// needs: Copy
+pub type ForestNodeID = usize;
+
+/// the actions that may follow, each leading to the rest of an interpretation
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ForestNode<K: Clone> {
+    /// whether an interpretation may end here
+    accept: bool,
+    edges: Vec<(Action<K>, ForestNodeID)>,
+}
+
+/// every interpretation as a DAG of actions, with common prefixes and common rests shared
+#[derive(Debug)]
+pub struct Forest<K: Clone> {
+    nodes: Vec<ForestNode<K>>,
+    index: HashMap<ForestNode<K>, ForestNodeID>,
+    root: Option<ForestNodeID>,
+}
+
+impl<K: Clone + Eq + Hash> Forest<K> {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            index: HashMap::new(),
+            root: None,
+        }
+    }
+
+    /// the same alternatives leading to the same rests are the same node
+    pub fn insert(&mut self, accept: bool, edges: Vec<(Action<K>, ForestNodeID)>) -> ForestNodeID {
+        let node = ForestNode { accept, edges };
+        if let Some(&id) = self.index.get(&node) {
+            return id;
+        }
+        let id = self.nodes.len();
+        self.nodes.push(node.clone());
+        self.index.insert(node, id);
+        id
+    }
+
+    pub fn set_root(&mut self, root: Option<ForestNodeID>) {
+        self.root = root;
+    }
+
+    pub fn size(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// number of interpretations, without enumerating them
+    pub fn count(&self) -> usize {
+        let mut counts = vec![0; self.nodes.len()];
+        // children are always inserted before their parents
+        for (id, node) in self.nodes.iter().enumerate() {
+            let rest = node.edges.iter().map(|&(_, child)| counts[child]);
+            counts[id] = rest.fold(node.accept as usize, usize::saturating_add);
+        }
+        self.root.map_or(0, |root| counts[root])
+    }
+
+    /// every interpretation, in the order they were found
+    pub fn enumerate(&self) -> Vec<Vec<Action<K>>> {
+        let mut res = Vec::new();
+        if let Some(root) = self.root {
+            self.enumerate_from(root, &mut Vec::new(), &mut res);
+        }
+        res
+    }
+
+    fn enumerate_from(
+        &self,
+        id: ForestNodeID,
+        actions: &mut Vec<Action<K>>,
+        res: &mut Vec<Vec<Action<K>>>,
+    ) {
+        let node = &self.nodes[id];
+        if node.accept {
+            res.push(actions.clone());
+        }
+        for (action, child) in &node.edges {
+            actions.push(action.clone());
+            self.enumerate_from(*child, actions, res);
+            actions.pop();
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash> Default for Forest<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Display + Clone + Ord> Forest<K> {
+    pub fn to_dot_graph(&self, style: &RenderStyle<K>) -> Result<String> {
+        let mut graph = style.to_dot_header();
+        for (id, node) in self.nodes.iter().enumerate() {
+            let shape = match node.accept {
+                true => "doublecircle",
+                false => "circle",
+            };
+            writeln!(graph, r#"    "{}" [label="", shape={}];"#, id, shape)?;
+            for (action, child) in &node.edges {
+                let label = format!("{action}").replace('"', "\\\"");
+                writeln!(graph, r#"    "{}" -> "{}" [label="{}"];"#, id, child, label)?;
+            }
+        }
+        graph.push_str("}\n");
+        Ok(graph)
+    }
+
+    pub fn to_png(&self, filename: String, style: &RenderStyle<K>) -> Result<()> {
+        if !is_dot_installed() {
+            return Err(Error::GraphvizDotNotInstalled);
+        }
+        let dot = self.to_dot_graph(style)?;
+
+        exec_dot(dot, vec![Format::Png.into(), CommandArg::Output(filename)])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_without_enumerating() {
+        // a chain of choices between two tokens, 2^n interpretations over 2n + 1 nodes
+        let mut forest = Forest::new();
+        let mut rest = forest.insert(true, Vec::new());
+        for _ in 0..40 {
+            let edges = vec![(Action::AddToken("a"), rest), (Action::AddToken("b"), rest)];
+            rest = forest.insert(false, edges);
+        }
+        forest.set_root(Some(rest));
+        assert_eq!(forest.size(), 41);
+        assert_eq!(forest.count(), 1 << 40);
+
+        let mut forest = Forest::new();
+        let end = forest.insert(true, Vec::new());
+        let edges = vec![(Action::AddToken("a"), end), (Action::AddToken("b"), end)];
+        let root = forest.insert(false, edges);
+        forest.set_root(Some(root));
+        let enumerated = forest.enumerate();
+        assert_eq!(enumerated.len(), forest.count());
+        assert_eq!(enumerated[1], vec![Action::AddToken("b")]);
+    }
+}
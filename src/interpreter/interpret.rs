@@ -1,101 +1,291 @@
 use super::action::Action;
 use super::error::{Error, Result};
+use super::forest::{Forest, ForestNodeID};
 use crate::cognitive::CognitiveModel;
-use crate::dialect::Dialect;
-use crate::lexicon::Lexicon;
-use crate::syntax::FeatureSet;
+use crate::dialect::{Dialect, Unknown};
+use crate::lexicon::suggest::closest;
+use crate::lexicon::{Lexicon, LexiconEntry};
+use crate::syntax::{FeatureSet, SyntaxValue};
 use crate::tokenizer::Tokenizer;
 use log::debug;
+use std::collections::HashSet;
 use std::fmt::Display;
+use std::hash::Hash;
 use std::str::FromStr;
 
 pub type Actions<K> = Vec<Action<K>>;
 
-pub fn interpret<D, C>(
-    dialect: &D,
-    text: &str,
-    target: &str,
-) -> Result<Vec<Actions<D::Token>>> where
+pub fn interpret<D, C>(dialect: &D, text: &str, target: &str) -> Result<Vec<Actions<D::Token>>>
+where
     D: Dialect,
-    D::Token: FromStr + Clone + Ord + Display,
-    C: CognitiveModel<D::Token> + Display
+    D::Token: FromStr + Clone + Ord + Hash + Display,
+    C: CognitiveModel<D::Token> + Display,
 {
+    Ok(interpret_forest::<D, C>(dialect, text, target)?.enumerate())
+}
+
+/// like `interpret`, but with the interpretations packed into a forest
+pub fn interpret_forest<D, C>(dialect: &D, text: &str, target: &str) -> Result<Forest<D::Token>>
+where
+    D: Dialect,
+    D::Token: FromStr + Clone + Ord + Hash + Display,
+    C: CognitiveModel<D::Token> + Display,
+{
+    let _scope = dialect.interner().enter();
     let target = D::Token::from_str(target).map_err(|_| Error::FromStr)?;
     let target = FeatureSet::from_category(target);
     let cogmodel = C::init(target);
 
-    let mut res = Vec::new();
-    let mut actions = Vec::new();
+    let mut forest = Forest::new();
 
-    step(dialect, &mut res, &mut actions, cogmodel, text)?;
+    let root = step(dialect, &mut forest, false, cogmodel, text)?;
+    forest.set_root(root);
+    /// returns the forest node of every interpretation of `text` from here, if any
     fn step<D, C>(
         dialect: &D,
-        res: &mut Vec<Actions<D::Token>>,
-        actions: &mut Actions<D::Token>,
+        forest: &mut Forest<D::Token>,
+        reordered: bool,
         cogmodel: C,
         text: &str,
-    ) -> Result<()> where
+    ) -> Result<Option<ForestNodeID>>
+    where
         D: Dialect,
-        D::Token: FromStr + Clone + Display,
-        C: CognitiveModel<D::Token> + Display
+        D::Token: FromStr + Clone + Eq + Hash + Display,
+        C: CognitiveModel<D::Token> + Display,
     {
         if text.is_empty() && cogmodel.understood() {
-            res.push(actions.clone());
-            return Ok(());
+            return Ok(Some(forest.insert(true, Vec::new())));
         }
 
+        let mut edges = Vec::new();
+
         if cogmodel.demand() {
             for (newtoken, remainder) in dialect.tokenizer().tokenize(text) {
                 debug!("model: {}", cogmodel);
                 debug!("newtoken: {}", newtoken);
                 debug!("remainder: {}", remainder);
 
-                actions.push(Action::AddToken(newtoken.clone()));
                 let mut cogmodel = cogmodel.clone();
-                if let Ok(()) = cogmodel.receive(newtoken) {
-                    step(dialect, res, actions, cogmodel, remainder)?;
+                if let Ok(()) = cogmodel.receive(newtoken.clone())
+                    && let Some(rest) = step(dialect, forest, false, cogmodel, remainder)?
+                {
+                    edges.push((Action::AddToken(newtoken), rest));
                 }
-                actions.pop();
             }
         }
 
+        let mut decided = false;
         if let Some(value) = cogmodel.wonder() {
-            for entry in dialect.lexicon().get_entries(value) {
+            for entry in lookup(dialect, value)? {
                 debug!("model: {}", cogmodel);
                 debug!("entry: {}", entry);
 
-                actions.push(Action::ApplyEntry(entry.clone()));
                 let mut cogmodel = cogmodel.clone();
-                if let Ok(()) = cogmodel.decide(entry) {
-                    step(dialect, res, actions, cogmodel, text)?;
+                if let Ok(()) = cogmodel.decide(entry.clone())
+                    && let Some(rest) = step(dialect, forest, false, cogmodel, text)?
+                {
+                    decided = true;
+                    edges.push((Action::ApplyEntry(Box::new(entry)), rest));
                 }
-                actions.pop();
             }
         }
 
-        Ok(())
+        // only reorder when no entry of the first argument leads to an interpretation, so that
+        // each interpretation is found once; reordering twice in a row could go back and forth
+        // forever
+        if !decided && !reordered {
+            for index in 1..cogmodel.pending() {
+                debug!("model: {}", cogmodel);
+                debug!("reorder: {}", index);
+
+                let mut cogmodel = cogmodel.clone();
+                if let Ok(()) = cogmodel.reorder(index)
+                    && let Some(rest) = step(dialect, forest, true, cogmodel, text)?
+                {
+                    edges.push((Action::Reorder(index), rest));
+                }
+            }
+        }
+
+        match edges.is_empty() {
+            true => Ok(None),
+            false => Ok(Some(forest.insert(false, edges))),
+        }
     }
 
-    Ok(res)
+    Ok(forest)
+}
+
+/// the entries of `value`, or what the dialect does with a token not in the lexicon
+fn lookup<D>(dialect: &D, value: &SyntaxValue<D::Token>) -> Result<HashSet<LexiconEntry<D::Token>>>
+where
+    D: Dialect,
+    D::Token: Display + Eq + Hash,
+{
+    let lexicon = dialect.lexicon();
+    let entries = lexicon.get_entries(value);
+    let SyntaxValue::Item(word) = value else {
+        return Ok(entries);
+    };
+    if !entries.is_empty() {
+        return Ok(entries);
+    }
+    match dialect.unknown() {
+        Unknown::Fail => Err(Error::UnknownWord(word.to_string())),
+        Unknown::Suggest => {
+            let known = lexicon.entries().into_iter();
+            let known = known.filter_map(|(from, _)| match from {
+                SyntaxValue::Item(item) => Some(item.to_string()),
+                SyntaxValue::Features(_) => None,
+            });
+            match closest(&word.to_string(), known) {
+                Some(known) => Err(Error::MisspelledWord(word.to_string(), known)),
+                None => Err(Error::UnknownWord(word.to_string())),
+            }
+        }
+        Unknown::Guess => Ok(lexicon
+            .unknown()
+            .into_iter()
+            .map(LexiconEntry::Guess)
+            .collect()),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cognitive::NaiveModel;
+    use crate::cognitive::{self, LambdaModel, NaiveModel};
     use crate::dialect::English;
     use crate::interner::GlobalKey;
+    use crate::interpreter::budget::peak;
+    use crate::syntax::FeatureSet;
+    use std::cell::Cell;
+    use std::time::{Duration, Instant};
 
     #[test]
     fn test_cogmodel() {
         let dialect = English::default();
         let res = interpret::<_, NaiveModel>(&dialect, "Hello, world!", "S").unwrap();
+        // the words are not in the lexicon, and the frozen interner keeps them unknown
         let shouldbe = vec![
             ["Hello", ",", "world", "!"]
-                .map(|s| Action::AddToken(GlobalKey::from_str(s).unwrap()))
+                .map(|s| Action::AddToken(GlobalKey::Unknown(s.to_string())))
                 .into_iter()
                 .collect::<Vec<_>>(),
         ];
         assert_eq!(res, shouldbe);
     }
+
+    #[test]
+    fn unknown_words() {
+        let mut dialect = English::default();
+        let sentence = "the chlid ate an apple.";
+        let result = interpret::<_, LambdaModel<_>>(&dialect, sentence, "Sentence");
+        assert!(matches!(result, Err(Error::MisspelledWord(word, known))
+            if word == "chlid" && known == "child"));
+
+        dialect.set_unknown(Unknown::Fail);
+        let result = interpret::<_, LambdaModel<_>>(&dialect, sentence, "Sentence");
+        assert!(matches!(result, Err(Error::UnknownWord(word)) if word == "chlid"));
+
+        // `chlid` is guessed to be a noun, from `[Unknown]`
+        dialect.set_unknown(Unknown::Guess);
+        let result = interpret::<_, LambdaModel<_>>(&dialect, sentence, "Sentence").unwrap();
+        assert_eq!(result.len(), 1);
+        assert!(result[0].iter().any(Action::is_guess));
+    }
+
+    #[test]
+    fn reorder_after_dead_end() {
+        // `did` in hand leads nowhere until the subject is reordered behind it
+        let dialect = English::default();
+        let sentence = "whose apple did the child eat?";
+        let result = interpret::<_, LambdaModel<_>>(&dialect, sentence, "Sentence").unwrap();
+        assert_eq!(result.len(), 1);
+        assert!(
+            result[0]
+                .iter()
+                .any(|action| matches!(action, Action::Reorder(_)))
+        );
+    }
+
+    #[test]
+    fn two_prepositions_in_budget() {
+        // nothing is kept of a dead end, so the search takes little memory however long it runs
+        let dialect = English::default();
+        let sentence = "the child ate an apple in the room in the room.";
+        let start = Instant::now();
+        let (forest, bytes) =
+            peak(|| interpret_forest::<_, LambdaModel<_>>(&dialect, sentence, "Sentence").unwrap());
+        assert_eq!(forest.count(), 3);
+        assert!(bytes < 16 << 20, "{bytes} bytes at most");
+        assert!(start.elapsed() < Duration::from_secs(60));
+    }
+
+    thread_local! {
+        static REORDERS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// a lambda model counting the reorders tried on it
+    #[derive(Clone)]
+    struct Counted(LambdaModel<GlobalKey>);
+
+    impl Display for Counted {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            self.0.fmt(f)
+        }
+    }
+
+    impl CognitiveModel<GlobalKey> for Counted {
+        fn init(target: FeatureSet<GlobalKey>) -> Self {
+            Counted(LambdaModel::init(target))
+        }
+        fn understood(&self) -> bool {
+            self.0.understood()
+        }
+        fn demand(&self) -> bool {
+            self.0.demand()
+        }
+        fn receive(&mut self, token: GlobalKey) -> std::result::Result<(), cognitive::Error> {
+            self.0.receive(token)
+        }
+        fn wonder(&self) -> Option<&SyntaxValue<GlobalKey>> {
+            self.0.wonder()
+        }
+        fn moved(&self) -> bool {
+            self.0.moved()
+        }
+        fn decide(
+            &mut self,
+            entry: LexiconEntry<GlobalKey>,
+        ) -> std::result::Result<(), cognitive::Error> {
+            self.0.decide(entry)
+        }
+        fn pending(&self) -> usize {
+            self.0.pending()
+        }
+        fn reorder(&mut self, index: usize) -> std::result::Result<(), cognitive::Error> {
+            REORDERS.with(|reorders| reorders.set(reorders.get() + 1));
+            self.0.reorder(index)
+        }
+    }
+
+    #[test]
+    fn few_reorders_in_examples() {
+        // a corner is only offered for what the expectation on top could not take otherwise
+        let dialect = English::default();
+        let examples = [
+            ("the child ate an apple in the room.", 2, 10_000),
+            ("the child did eat an apple.", 1, 4_000),
+            ("did the child eat an apple?", 1, 4_000),
+            ("whose apple did the child eat?", 1, 5_000),
+        ];
+        for (sentence, count, most) in examples {
+            REORDERS.with(|reorders| reorders.set(0));
+            let result = interpret::<_, Counted>(&dialect, sentence, "Sentence").unwrap();
+            assert_eq!(result.len(), count);
+            let reorders = REORDERS.with(Cell::get);
+            assert!(reorders <= most, "{reorders} reorders for {sentence}");
+        }
+    }
 }
@@ -1,8 +1,16 @@
 mod action;
+#[cfg(test)]
+mod budget;
+mod compare;
 mod error;
 mod follow;
+mod forest;
 mod interpret;
+mod validate;
 
+pub use action::Action;
+pub use compare::compare;
 pub use error::Error;
-pub use follow::follow;
-pub use interpret::interpret;
+pub use follow::{follow, follow_steps};
+pub use interpret::{interpret, interpret_forest};
+pub use validate::cross_validate;
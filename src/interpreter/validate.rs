@@ -0,0 +1,309 @@
+use super::action::Action;
+use super::error::{Error, Result};
+use crate::cognitive::CognitiveModel;
+use crate::syntax::FeatureSet;
+use log::debug;
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
+
+/// where a searching model and a replaying model first disagree on an interpretation
+#[derive(Debug)]
+pub enum Divergence<K: Clone> {
+    Step {
+        index: usize,
+        action: Action<K>,
+        /// whether the searching model is the one accepting the action
+        searched: bool,
+        error: String,
+    },
+    /// only one of the models wonders about a moved copy after the action at `index`
+    Moved {
+        index: usize,
+        searched: bool,
+    },
+    Understood {
+        searched: bool,
+    },
+}
+
+impl<K: Display + Clone> Display for Divergence<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let side = |searched: bool| match searched {
+            true => ("searching", "replaying"),
+            false => ("replaying", "searching"),
+        };
+        match self {
+            Divergence::Step {
+                index,
+                action,
+                searched,
+                error,
+            } => {
+                let (accepted, rejected) = side(*searched);
+                write!(
+                    f,
+                    "action {index} [{action}] is accepted by the {accepted} model \
+                     but rejected by the {rejected} model: {error}"
+                )
+            }
+            Divergence::Moved { index, searched } => {
+                let (moved, unmoved) = side(*searched);
+                write!(
+                    f,
+                    "after action {index}, the {moved} model wonders about a moved copy \
+                     but the {unmoved} model does not"
+                )
+            }
+            Divergence::Understood { searched } => {
+                let (understood, confused) = side(*searched);
+                write!(
+                    f,
+                    "the {understood} model understood the sentence but the {confused} model did not"
+                )
+            }
+        }
+    }
+}
+
+/// replays `actions` in both models side by side, returning the first disagreement;
+/// an action rejected by both is an error, as `actions` is not an interpretation then
+pub fn cross_validate<K, S, R>(
+    target: &str,
+    actions: Vec<Action<K>>,
+) -> Result<Option<Divergence<K>>>
+where
+    K: Clone + FromStr + Ord + Debug,
+    S: CognitiveModel<K> + Debug,
+    R: CognitiveModel<K> + Debug,
+{
+    let target = K::from_str(target).map_err(|_| Error::FromStr)?;
+    let target = FeatureSet::from_category(target);
+    let mut searching = S::init(target.clone());
+    let mut replaying = R::init(target);
+
+    for (index, action) in actions.into_iter().enumerate() {
+        debug!("{action:?}");
+        let searched = apply(&mut searching, action.clone());
+        let replayed = apply(&mut replaying, action.clone());
+        let (searched, error) = match (searched, replayed) {
+            (Ok(()), Ok(())) => match searching.moved() == replaying.moved() {
+                true => continue,
+                false => {
+                    let searched = searching.moved();
+                    return Ok(Some(Divergence::Moved { index, searched }));
+                }
+            },
+            (Err(error), Err(_)) => return Err(Error::from(error)),
+            (Ok(()), Err(error)) => (true, error),
+            (Err(error), Ok(())) => (false, error),
+        };
+        let error = format!("{error:?}");
+        return Ok(Some(Divergence::Step {
+            index,
+            action,
+            searched,
+            error,
+        }));
+    }
+
+    match (searching.understood(), replaying.understood()) {
+        (true, false) => Ok(Some(Divergence::Understood { searched: true })),
+        (false, true) => Ok(Some(Divergence::Understood { searched: false })),
+        _ => Ok(None),
+    }
+}
+
+fn apply<K: Clone, C: CognitiveModel<K>>(
+    cogmodel: &mut C,
+    action: Action<K>,
+) -> std::result::Result<(), crate::cognitive::Error> {
+    match action {
+        Action::AddToken(token) => cogmodel.receive(token),
+        Action::ApplyEntry(entry) => cogmodel.decide(*entry),
+        Action::Reorder(index) => cogmodel.reorder(index),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cognitive::{LambdaModel, TreeModel};
+    use crate::dialect::{Dialect, English, Unknown};
+    use crate::interner::{GlobalKey, Interner};
+    use crate::interpreter::interpret;
+    use crate::lexicon::SimpleLexicon;
+    use crate::lexicon::parser::{LexiconParser, PestLexiconParser};
+    use crate::tokenizer::SimpleTokenizer;
+
+    const CATEGORIES: [&str; 5] = ["Qa", "Qb", "Qc", "Qd", "Qe"];
+    const WORDS: [&str; 4] = ["wa", "wb", "wc", "wd"];
+
+    #[derive(Debug)]
+    struct RandomDialect {
+        lexicon: SimpleLexicon<GlobalKey>,
+        tokenizer: SimpleTokenizer,
+        interner: Interner,
+    }
+
+    impl Default for RandomDialect {
+        fn default() -> Self {
+            Self {
+                lexicon: SimpleLexicon::new(),
+                tokenizer: SimpleTokenizer,
+                interner: Interner::new(),
+            }
+        }
+    }
+
+    impl Dialect for RandomDialect {
+        type Token = GlobalKey;
+
+        fn name(&self) -> &str {
+            "Random"
+        }
+        fn lexicon(&self) -> &impl crate::lexicon::Lexicon<Self::Token> {
+            &self.lexicon
+        }
+        fn tokenizer(&self) -> &impl crate::tokenizer::Tokenizer<Self::Token> {
+            &self.tokenizer
+        }
+        fn interner(&self) -> &Interner {
+            &self.interner
+        }
+        fn unknown(&self) -> Unknown {
+            // a word without entries is not an error, there is just nothing to guess
+            Unknown::Guess
+        }
+    }
+
+    /// xorshift, so that a failing seed can be replayed
+    struct Random(u64);
+
+    impl Random {
+        fn below(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as usize
+        }
+        fn category_above(&mut self, index: usize) -> usize {
+            index + 1 + self.below(CATEGORIES.len() - index - 1)
+        }
+        /// `category`, possibly with a value, a variable or a constraint of the feature `f`
+        fn features(&mut self, category: &str) -> String {
+            match self.below(8) {
+                0 => format!("{category}-f"),
+                1 => format!("{category}-x"),
+                2 => format!("{category}-y"),
+                3 => format!("{category}-f!=x"),
+                4 => format!("{category}-!f"),
+                _ => category.to_string(),
+            }
+        }
+        fn any(&mut self) -> String {
+            let category = CATEGORIES[self.below(CATEGORIES.len())];
+            self.features(category)
+        }
+        /// the left corner of a lambda on the `from` side is taken as already in hand
+        fn corner(&mut self, index: usize) -> String {
+            let corner = CATEGORIES[self.category_above(index)];
+            let corner = self.features(corner);
+            match self.below(2) {
+                0 => format!("MOVED({corner})"),
+                _ => corner.to_string(),
+            }
+        }
+        /// a node whose result and corners are above `index`, so that functional entries
+        /// cannot rewrite a constituent in a cycle
+        fn node(&mut self, index: usize) -> String {
+            let to = self.category_above(index);
+            let result = self.features(CATEGORIES[to]);
+            match self.below(5) {
+                0 => result,
+                1 => format!("({} >> {})", self.any(), result),
+                2 => format!("(({} > {}) > {})", self.corner(index), self.any(), result),
+                3 if to + 1 < CATEGORIES.len() => {
+                    let next = CATEGORIES[self.category_above(to)];
+                    let next = self.features(next);
+                    format!("({} > ({} > {}))", self.any(), result, next)
+                }
+                _ => format!("({} > {})", self.any(), result),
+            }
+        }
+        fn lexicon(&mut self) -> String {
+            let mut lexicon = String::from("[Features]\nf = x, y\n[Functional]\n");
+            for _ in 0..6 {
+                let key = self.below(CATEGORIES.len() - 1);
+                let node = self.node(key);
+                let key = self.features(CATEGORIES[key]);
+                lexicon.push_str(&format!("{} = {}\n", key, node));
+            }
+            lexicon.push_str("[Lexical]\n");
+            for word in WORDS {
+                for _ in 0..=self.below(2) {
+                    let index = self.below(CATEGORIES.len() - 1);
+                    lexicon.push_str(&format!("{} = {}\n", word, self.node(index)));
+                }
+            }
+            lexicon
+        }
+        fn sentence(&mut self) -> String {
+            let words = (0..=self.below(4)).map(|_| WORDS[self.below(WORDS.len())]);
+            words.collect::<Vec<_>>().join(" ")
+        }
+    }
+
+    #[test]
+    fn cross_validate_random_lexicons() {
+        let target = CATEGORIES[CATEGORIES.len() - 1];
+        let mut interpretations = 0;
+        for seed in 1..=500u64 {
+            let mut random = Random(seed.wrapping_mul(0x9E3779B97F4A7C15));
+            let lexicon = random.lexicon();
+            let mut dialect = RandomDialect::default();
+            // each lexicon has symbols of its own, as it would as a dialect
+            let _scope = dialect.interner.enter();
+            PestLexiconParser::parse_str(&mut dialect.lexicon, &lexicon).unwrap();
+
+            for _ in 0..4 {
+                let sentence = random.sentence();
+                let result = interpret::<_, LambdaModel<_>>(&dialect, &sentence, target).unwrap();
+                for actions in result {
+                    interpretations += 1;
+                    let divergence =
+                        cross_validate::<_, LambdaModel<_>, TreeModel<_>>(target, actions);
+                    match divergence {
+                        Ok(None) => (),
+                        Ok(Some(divergence)) => {
+                            panic!("seed {seed}, \"{sentence}\": {divergence}\n{lexicon}")
+                        }
+                        Err(error) => panic!("seed {seed}, \"{sentence}\": {error}\n{lexicon}"),
+                    }
+                }
+            }
+        }
+        assert!(interpretations > 0);
+    }
+
+    #[test]
+    fn models_agree_on_moved_copies() {
+        // `ate` leaves a copy `MOVED(NuH)` of itself, which is decided on after the object
+        let dialect = English::default();
+        let sentence = "the child ate an apple.";
+        let result = interpret::<_, LambdaModel<_>>(&dialect, sentence, "Sentence").unwrap();
+        assert_eq!(result.len(), 1);
+
+        let _scope = dialect.interner().enter();
+        let target = FeatureSet::from_category(GlobalKey::from_str("Sentence").unwrap());
+        let mut lambda = LambdaModel::init(target.clone());
+        let mut tree = TreeModel::init(target);
+        let mut moved = 0;
+        for action in result[0].iter().cloned() {
+            apply(&mut lambda, action.clone()).unwrap();
+            apply(&mut tree, action).unwrap();
+            assert_eq!(lambda.moved(), tree.moved());
+            moved += lambda.moved() as usize;
+        }
+        assert!(moved > 0);
+    }
+}
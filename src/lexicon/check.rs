@@ -0,0 +1,426 @@
+use super::{KeyedNode, Lexicon, LexiconEntry, LexiconNode, SimpleLexicon, inflect};
+use crate::syntax::{Bindings, FeatureSet, SyntaxValue};
+use std::collections::BTreeSet;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+/// something in a lexicon that takes part in no interpretation, or may rewrite forever
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Finding<K> {
+    /// a functional entry whose result leads to no expectation raised from the target
+    Unreachable(KeyedNode<K>),
+    /// functional entries consuming nothing, which may rewrite into one another forever
+    Cycle(Vec<KeyedNode<K>>),
+    /// a category expected or looked up, but in no result of any entry
+    Unproduced(K),
+    /// a lexical item whose features key no functional entry and meet no expectation
+    Dead(K, LexiconNode<K>),
+}
+
+impl<K: Display> Display for Finding<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Finding::Unreachable((key, node)) => write!(f, "unreachable entry: {key} = {node}"),
+            Finding::Cycle(entries) => {
+                let entries = entries
+                    .iter()
+                    .map(|(key, node)| format!("{key} = {node}"))
+                    .collect::<Vec<_>>();
+                write!(
+                    f,
+                    "cycle of entries consuming nothing: {}",
+                    entries.join(", ")
+                )
+            }
+            Finding::Unproduced(category) => {
+                write!(f, "category produced by no entry: {category}")
+            }
+            Finding::Dead(item, node) => {
+                write!(f, "lexical item used by no entry: {item} = {node}")
+            }
+        }
+    }
+}
+
+/// the expectations and the features in hand of every entry, with what the entries are
+/// looked up by, so that each lookup is made once
+struct Graph<K: Debug + Clone + Ord + Hash> {
+    functional: Vec<KeyedNode<K>>,
+    /// the lexical entries, then the inflections of those that are stems
+    lexical: Vec<(K, LexiconNode<K>)>,
+    /// the entry each lexical entry is, or is an inflection of
+    stems: Vec<usize>,
+    /// the functional entries the result of each functional entry is looked up by
+    functional_feeds: Vec<Vec<usize>>,
+    lexical_feeds: Vec<Vec<usize>>,
+}
+
+/// the findings about `lexicon`, for interpretations as `target`, in a stable order
+pub fn check<K>(lexicon: &SimpleLexicon<K>, target: K) -> Vec<Finding<K>>
+where
+    K: Debug + Display + Clone + Ord + Hash,
+{
+    let functional = lexicon.functional_entries();
+    let mut lexical = lexicon
+        .lexical_entries()
+        .map(|(item, node)| (item.clone(), node.clone()))
+        .collect::<Vec<_>>();
+    let mut stems = (0..lexical.len()).collect::<Vec<_>>();
+    let mut cells = lexicon
+        .rules()
+        .into_iter()
+        .map(|rule| (None, rule.features))
+        .collect::<Vec<_>>();
+    for paradigm in lexicon.paradigms() {
+        let lemma = Some(paradigm.lemma);
+        cells.extend(
+            paradigm
+                .cells
+                .into_iter()
+                .map(|(cell, _)| (lemma.clone(), cell)),
+        );
+    }
+    for (lemma, cell) in cells {
+        for stem in 0..stems.len() {
+            let (item, node) = &lexical[stem];
+            if stems[stem] != stem || lemma.as_ref().is_some_and(|lemma| lemma != item) {
+                continue;
+            }
+            if let Some(inflected) = inflect(&cell, node) {
+                lexical.push((item.clone(), inflected));
+                stems.push(stem);
+            }
+        }
+    }
+    let feeds = |node: &LexiconNode<K>| match node.get_features_right() {
+        Some(result) => feeds(lexicon, &functional, result),
+        None => Vec::new(),
+    };
+    let graph = Graph {
+        functional_feeds: functional.iter().map(|(_, node)| feeds(node)).collect(),
+        lexical_feeds: lexical.iter().map(|(_, node)| feeds(node)).collect(),
+        functional,
+        lexical,
+        stems,
+    };
+
+    let mut findings = Vec::new();
+    findings.extend(unreachable(&graph, target.clone()));
+    findings.extend(cycles(&graph));
+    findings.extend(unproduced(&graph, target.clone()));
+    findings.extend(dead(&graph, target));
+    findings.sort();
+    findings
+}
+
+/// the functional entries that `fs` in hand may be rewritten by
+fn feeds<K>(
+    lexicon: &SimpleLexicon<K>,
+    functional: &[KeyedNode<K>],
+    fs: &FeatureSet<K>,
+) -> Vec<usize>
+where
+    K: Debug + Display + Clone + Ord + Hash,
+{
+    let entries = lexicon.get_entries(&SyntaxValue::Features(fs.clone()));
+    let found = |key: &FeatureSet<K>, node: &LexiconNode<K>| {
+        entries.iter().any(|entry| match entry {
+            LexiconEntry::Functional { to, key: k, .. } => k == key && to == node,
+            LexiconEntry::Lexical(..) | LexiconEntry::Guess(_) => false,
+        })
+    };
+    let indices = functional.iter().enumerate();
+    indices
+        .filter(|(_, (key, node))| found(key, node))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// `fs` in hand meets the expectation `goal`, with their variables kept apart
+fn meets<K: Clone + Ord>(goal: &FeatureSet<K>, fs: &FeatureSet<K>) -> bool {
+    let mut fs = fs.clone();
+    fs.offset_variables(goal.next_variable());
+    goal.unify(&fs, &mut Bindings::new())
+}
+
+/// the features `node` expects to be given, and those it puts in hand, its result included
+fn expectations<'a, K>(
+    node: &'a LexiconNode<K>,
+    given: &mut Vec<&'a FeatureSet<K>>,
+    in_hand: &mut Vec<&'a FeatureSet<K>>,
+) {
+    match node {
+        LexiconNode::Lambda { from, to, .. } => {
+            argument(from, given, in_hand);
+            expectations(to, given, in_hand);
+        }
+        _ => in_hand.extend(node.get_features_right()),
+    }
+}
+
+/// an argument is built from what is in hand, e.g., `(MOVED(AuxH) > AuxP)` from `AuxH`
+fn argument<'a, K>(
+    node: &'a LexiconNode<K>,
+    given: &mut Vec<&'a FeatureSet<K>>,
+    in_hand: &mut Vec<&'a FeatureSet<K>>,
+) {
+    match node {
+        LexiconNode::Lambda { from, to, .. } => {
+            in_hand.extend(from.features());
+            argument(to, given, in_hand);
+        }
+        LexiconNode::Moved { from } => in_hand.push(from),
+        _ => given.extend(node.get_features_right()),
+    }
+}
+
+/// the categories of `fs`, e.g., `DetP` in `DetP-sg`
+fn categories<K: Clone>(fs: &FeatureSet<K>) -> impl Iterator<Item = K> + '_ {
+    fs.iter()
+        .filter(|(_, value)| value.is_none())
+        .map(|(category, _)| category.clone())
+}
+
+/// an entry is reachable if its result meets a reachable expectation, or is looked up by
+/// a reachable entry, and its own expectations are then reachable
+fn unreachable<K>(graph: &Graph<K>, target: K) -> Vec<Finding<K>>
+where
+    K: Debug + Clone + Ord + Hash,
+{
+    let target = FeatureSet::from_category(target);
+    let mut goals = vec![&target];
+    let mut reachable = vec![false; graph.functional.len()];
+    let mut reachable_lexical = vec![false; graph.lexical.len()];
+    let leads =
+        |node: &LexiconNode<K>, feeds: &[usize], goals: &[&FeatureSet<K>], reachable: &[bool]| {
+            let meets_goal = match node.get_features_right() {
+                Some(result) => goals.iter().any(|goal| meets(goal, result)),
+                None => false,
+            };
+            meets_goal || feeds.iter().any(|&index| reachable[index])
+        };
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (index, (_, node)) in graph.functional.iter().enumerate() {
+            if !reachable[index] && leads(node, &graph.functional_feeds[index], &goals, &reachable)
+            {
+                reachable[index] = true;
+                changed = true;
+                expectations(node, &mut goals, &mut Vec::new());
+            }
+        }
+        for (index, (_, node)) in graph.lexical.iter().enumerate() {
+            if !reachable_lexical[index]
+                && leads(node, &graph.lexical_feeds[index], &goals, &reachable)
+            {
+                reachable_lexical[index] = true;
+                changed = true;
+                expectations(node, &mut goals, &mut Vec::new());
+            }
+        }
+    }
+
+    let entries = graph.functional.iter().zip(reachable);
+    entries
+        .filter(|(_, reachable)| !reachable)
+        .map(|(entry, _)| Finding::Unreachable(entry.clone()))
+        .collect()
+}
+
+/// strongly connected components of the entries consuming nothing, by Tarjan's algorithm
+fn cycles<K>(graph: &Graph<K>) -> Vec<Finding<K>>
+where
+    K: Debug + Clone + Ord + Hash,
+{
+    let consumes = |index: usize| matches!(graph.functional[index].1, LexiconNode::Lambda { .. });
+    let edges = (0..graph.functional.len())
+        .map(|index| match consumes(index) {
+            true => Vec::new(),
+            false => {
+                let feeds = graph.functional_feeds[index].iter().copied();
+                let feeds = feeds.filter(|&next| !consumes(next));
+                feeds
+                    .filter(|&next| next != index || !refines(&graph.functional[index]))
+                    .collect()
+            }
+        })
+        .collect::<Vec<Vec<usize>>>();
+
+    let mut tarjan = Tarjan {
+        edges: &edges,
+        index: vec![None; edges.len()],
+        low: vec![0; edges.len()],
+        stack: Vec::new(),
+        on_stack: vec![false; edges.len()],
+        next: 0,
+        components: Vec::new(),
+    };
+    for index in 0..edges.len() {
+        if tarjan.index[index].is_none() {
+            tarjan.visit(index);
+        }
+    }
+
+    let components = tarjan.components.into_iter();
+    components
+        .filter(|component| component.len() > 1 || edges[component[0]].contains(&component[0]))
+        .map(|component| {
+            let entries = component
+                .into_iter()
+                .map(|index| graph.functional[index].clone());
+            let mut entries = entries.collect::<Vec<_>>();
+            entries.sort();
+            Finding::Cycle(entries)
+        })
+        .collect()
+}
+
+/// an entry adding a feature, e.g., `DetP = DetP-case`, does not apply to its own result,
+/// as the feature would be projected onto itself
+fn refines<K: Clone + Ord>((key, node): &KeyedNode<K>) -> bool {
+    let features = node.get_features_right().into_iter();
+    features
+        .flat_map(FeatureSet::categories)
+        .any(|category| !key.contains_key(category))
+}
+
+struct Tarjan<'a> {
+    edges: &'a [Vec<usize>],
+    index: Vec<Option<usize>>,
+    low: Vec<usize>,
+    stack: Vec<usize>,
+    on_stack: Vec<bool>,
+    next: usize,
+    components: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, node: usize) {
+        self.index[node] = Some(self.next);
+        self.low[node] = self.next;
+        self.next += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        for &next in &self.edges[node] {
+            match self.index[next] {
+                None => {
+                    self.visit(next);
+                    self.low[node] = self.low[node].min(self.low[next]);
+                }
+                Some(index) if self.on_stack[next] => self.low[node] = self.low[node].min(index),
+                Some(_) => (),
+            }
+        }
+
+        if Some(self.low[node]) == self.index[node] {
+            let mut component = Vec::new();
+            while let Some(top) = self.stack.pop() {
+                self.on_stack[top] = false;
+                component.push(top);
+                if top == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+fn unproduced<K>(graph: &Graph<K>, target: K) -> Vec<Finding<K>>
+where
+    K: Debug + Clone + Ord + Hash,
+{
+    let mut given = Vec::new();
+    let mut in_hand = Vec::new();
+    let nodes = graph.functional.iter().map(|(_, node)| node);
+    for node in nodes.chain(graph.lexical.iter().map(|(_, node)| node)) {
+        expectations(node, &mut given, &mut in_hand);
+    }
+    given.extend(graph.functional.iter().map(|(key, _)| key));
+
+    let produced = in_hand
+        .into_iter()
+        .flat_map(categories)
+        .collect::<BTreeSet<_>>();
+    let mut wanted = given
+        .into_iter()
+        .flat_map(categories)
+        .collect::<BTreeSet<_>>();
+    wanted.insert(target);
+    wanted
+        .difference(&produced)
+        .map(|category| Finding::Unproduced(category.clone()))
+        .collect()
+}
+
+fn dead<K>(graph: &Graph<K>, target: K) -> Vec<Finding<K>>
+where
+    K: Debug + Clone + Ord + Hash,
+{
+    let target = FeatureSet::from_category(target);
+    let mut given = vec![&target];
+    let nodes = graph.functional.iter().map(|(_, node)| node);
+    for node in nodes.chain(graph.lexical.iter().map(|(_, node)| node)) {
+        expectations(node, &mut given, &mut Vec::new());
+    }
+
+    // a stem is used if any of its inflections is
+    let mut used = vec![false; graph.lexical.len()];
+    let items = graph.lexical.iter().zip(&graph.lexical_feeds);
+    for (index, ((_, node), feeds)) in items.enumerate() {
+        let meets_goal = match node.get_features_right() {
+            Some(result) => given.iter().any(|goal| meets(goal, result)),
+            None => false,
+        };
+        used[graph.stems[index]] |= !feeds.is_empty() || meets_goal;
+    }
+    let stems = graph.lexical.iter().zip(used).enumerate();
+    stems
+        .filter(|(index, (_, used))| graph.stems[*index] == *index && !used)
+        .map(|(_, ((item, node), _))| Finding::Dead(item.clone(), node.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interner::GlobalKey;
+    use crate::lexicon::parser::{LexiconParser, PestLexiconParser};
+
+    #[test]
+    fn check_lexicon() {
+        let input = "[Functional]
+DetH = (NounP > DetP)
+DetP = (VerbP > Sentence)
+AdvP = (VerbP > VerbP)
+NounP = (DetH > AdjP)
+A = B
+B = A
+[Lexical]
+the = DetH
+child = NounP
+ran = VerbP
+very = AdvH
+";
+        let mut lexicon: SimpleLexicon<GlobalKey> = SimpleLexicon::new();
+        PestLexiconParser::parse_str(&mut lexicon, input).unwrap();
+        let target = "Sentence".parse().unwrap();
+        // keys are ordered as they are interned, shared across tests
+        let mut findings = check(&lexicon, target)
+            .iter()
+            .map(|finding| format!("{finding}"))
+            .collect::<Vec<_>>();
+        findings.sort();
+        assert_eq!(findings.len(), 6);
+        assert_eq!(findings[0], "category produced by no entry: AdvP");
+        assert!(findings[1].starts_with("cycle of entries consuming nothing: "));
+        assert!(findings[1].contains("A = B") && findings[1].contains("B = A"));
+        assert_eq!(findings[2], "lexical item used by no entry: very = AdvH");
+        assert_eq!(findings[3], "unreachable entry: A = B");
+        assert_eq!(findings[4], "unreachable entry: B = A");
+        assert_eq!(findings[5], "unreachable entry: NounP = (DetH > AdjP)");
+    }
+}
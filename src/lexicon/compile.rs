@@ -0,0 +1,413 @@
+use super::parser::KeyType;
+use super::serialize::{features, node_source};
+use super::{Declaration, Lexicon, LexiconNode, Paradigm, SimpleLexicon, SuffixRule};
+use crate::syntax::{Constraint, FeatureSet, SyntaxValue};
+use derive_more::{Display, From};
+use memmap2::Mmap;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+
+/// the first bytes of a compiled lexicon
+const MAGIC: &[u8; 8] = b"LANGBDA\0";
+/// bumped whenever the layout below changes, as older files are then rejected
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Display, From)]
+pub enum CompileError {
+    #[display("Not a compiled lexicon")]
+    Magic,
+    #[display("Compiled with version {} of the format, not {}", _0, VERSION)]
+    #[from(ignore)]
+    Version(u32),
+    #[display("Compiled lexicon cut short")]
+    Truncated,
+    #[display("Compiled lexicon has no symbol {}", _0)]
+    #[from(ignore)]
+    Symbol(u32),
+    #[display("Compiled symbol {} cannot be parsed", _0)]
+    #[from(ignore)]
+    FromStr(String),
+    #[display("Compiled lexicon has an unknown tag {}", _0)]
+    #[from(ignore)]
+    Tag(u8),
+    #[display("Compiled lexicon has a string that is not UTF-8")]
+    Utf8,
+    StdIO(std::io::Error),
+}
+
+impl std::error::Error for CompileError {}
+
+/// `lexicon` as loaded, with its entries expanded, in a file that loads without parsing:
+/// the magic bytes and the version, the table of its symbols, then its declarations, rules,
+/// entries, paradigms and unknown nodes, each written by index into the table; entries and
+/// features are written in the order of their spelling, so that a lexicon always compiles to
+/// the same bytes
+pub fn compile<K: KeyType>(lexicon: &SimpleLexicon<K>) -> Vec<u8> {
+    let mut writer = Writer::default();
+    let declarations = lexicon.declarations();
+    writer.len(declarations.len());
+    for declaration in &declarations {
+        writer.symbol(&declaration.category);
+        writer.option(declaration.value.as_ref());
+        writer.option(declaration.supertype.as_ref());
+    }
+    let rules = lexicon.rules();
+    writer.len(rules.len());
+    for rule in &rules {
+        writer.string(&rule.suffix);
+        writer.features(&rule.features);
+    }
+    let mut entries = lexicon.entries();
+    entries.sort_by_cached_key(|(from, node)| {
+        let from = match from {
+            SyntaxValue::Item(item) => item.to_string(),
+            SyntaxValue::Features(fs) => features(fs),
+        };
+        (from, node_source(node))
+    });
+    writer.len(entries.len());
+    for (from, node) in &entries {
+        writer.value(from);
+        writer.node(node);
+    }
+    let paradigms = lexicon.paradigms();
+    writer.len(paradigms.len());
+    for paradigm in &paradigms {
+        writer.symbol(&paradigm.lemma);
+        writer.len(paradigm.cells.len());
+        for (cell, form) in &paradigm.cells {
+            writer.features(cell);
+            writer.symbol(form);
+        }
+    }
+    let unknown = lexicon.unknown();
+    writer.len(unknown.len());
+    unknown.iter().for_each(|node| writer.node(node));
+
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend(VERSION.to_le_bytes());
+    bytes.extend((writer.symbols.len() as u32).to_le_bytes());
+    for symbol in &writer.symbols {
+        bytes.extend((symbol.len() as u32).to_le_bytes());
+        bytes.extend(symbol.as_bytes());
+    }
+    bytes.extend(writer.body);
+    bytes
+}
+
+/// the lexicon compiled at `path`, mapped into memory and read in place; its symbols are
+/// interned by the current interner
+pub fn open<K: KeyType>(path: impl AsRef<Path>) -> Result<SimpleLexicon<K>, CompileError> {
+    let file = File::open(path)?;
+    // SAFETY: the map is only read while decoding, and everything read is copied out of it
+    let map = unsafe { Mmap::map(&file)? };
+    let mut lexicon = SimpleLexicon::new();
+    decode(&map, &mut lexicon)?;
+    Ok(lexicon)
+}
+
+/// adds the compiled lexicon in `bytes` to `lexicon`, as if it were parsed
+pub fn decode<K: KeyType>(bytes: &[u8], lexicon: &mut impl Lexicon<K>) -> Result<(), CompileError> {
+    let mut reader = Reader {
+        bytes,
+        symbols: Vec::new(),
+    };
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(CompileError::Magic);
+    }
+    match reader.u32()? {
+        VERSION => {}
+        version => return Err(CompileError::Version(version)),
+    }
+    for _ in 0..reader.u32()? {
+        let symbol = reader.string()?;
+        let key = K::from_str(symbol).map_err(|_| CompileError::FromStr(symbol.to_string()))?;
+        reader.symbols.push(key);
+    }
+
+    for _ in 0..reader.u32()? {
+        lexicon.add_declaration(Declaration {
+            category: reader.symbol()?,
+            value: reader.option()?,
+            supertype: reader.option()?,
+        });
+    }
+    for _ in 0..reader.u32()? {
+        lexicon.add_rule(SuffixRule {
+            suffix: reader.string()?.to_string(),
+            features: reader.features()?,
+        });
+    }
+    for _ in 0..reader.u32()? {
+        let from = reader.value()?;
+        lexicon.add_entry(from, reader.node()?);
+    }
+    for _ in 0..reader.u32()? {
+        let lemma = reader.symbol()?;
+        let mut cells = Vec::new();
+        for _ in 0..reader.u32()? {
+            cells.push((reader.features()?, reader.symbol()?));
+        }
+        lexicon.add_paradigm(Paradigm { lemma, cells });
+    }
+    for _ in 0..reader.u32()? {
+        lexicon.add_unknown(reader.node()?);
+    }
+    Ok(())
+}
+
+/// the body of a compiled lexicon, with the symbols it refers to by index
+#[derive(Default)]
+struct Writer {
+    symbols: Vec<String>,
+    indices: HashMap<String, u32>,
+    body: Vec<u8>,
+}
+
+impl Writer {
+    fn u32(&mut self, value: u32) {
+        self.body.extend(value.to_le_bytes());
+    }
+    fn len(&mut self, len: usize) {
+        self.u32(len as u32);
+    }
+    fn string(&mut self, string: &str) {
+        self.len(string.len());
+        self.body.extend(string.as_bytes());
+    }
+    fn symbol<K: KeyType>(&mut self, key: &K) {
+        let symbol = key.to_string();
+        let next = self.symbols.len() as u32;
+        let index = *self.indices.entry(symbol.clone()).or_insert(next);
+        if index == next {
+            self.symbols.push(symbol);
+        }
+        self.u32(index);
+    }
+    fn option<K: KeyType>(&mut self, key: Option<&K>) {
+        match key {
+            Some(key) => {
+                self.body.push(1);
+                self.symbol(key);
+            }
+            None => self.body.push(0),
+        }
+    }
+    fn features<K: KeyType>(&mut self, fs: &FeatureSet<K>) {
+        let values = spelled(fs.iter());
+        self.len(values.len());
+        for (category, value) in values {
+            self.symbol(category);
+            self.option(value.as_ref());
+        }
+        let variables = fs
+            .categories()
+            .filter_map(|category| Some((category, fs.get_variable(category)?)));
+        let variables = spelled(variables);
+        self.len(variables.len());
+        for (category, variable) in variables {
+            self.symbol(category);
+            self.len(variable);
+        }
+        // constrained categories are either variables or absent
+        let constraints = fs
+            .categories()
+            .chain(fs.absent())
+            .filter_map(|category| Some((category, fs.get_constraint(category)?)));
+        let constraints = spelled(constraints);
+        self.len(constraints.len());
+        for (category, constraint) in constraints {
+            self.symbol(category);
+            let values = match constraint {
+                Constraint::OneOf(values) => {
+                    self.body.push(0);
+                    values
+                }
+                Constraint::NoneOf(values) => {
+                    self.body.push(1);
+                    values
+                }
+                Constraint::Absent => {
+                    self.body.push(2);
+                    continue;
+                }
+            };
+            let values = spelled(values.iter().map(|value| (value, ())));
+            self.len(values.len());
+            values.iter().for_each(|(value, _)| self.symbol(*value));
+        }
+    }
+    fn value<K: KeyType>(&mut self, value: &SyntaxValue<K>) {
+        match value {
+            SyntaxValue::Item(item) => {
+                self.body.push(0);
+                self.symbol(item);
+            }
+            SyntaxValue::Features(fs) => {
+                self.body.push(1);
+                self.features(fs);
+            }
+        }
+    }
+    fn node<K: KeyType>(&mut self, node: &LexiconNode<K>) {
+        match node {
+            LexiconNode::Value { value } => {
+                self.body.push(0);
+                self.value(value);
+            }
+            LexiconNode::Lambda { from, to, project } => {
+                self.body.push(1);
+                self.node(from);
+                self.node(to);
+                self.body.push(*project as u8);
+            }
+            LexiconNode::Moved { from } => {
+                self.body.push(2);
+                self.features(from);
+            }
+        }
+    }
+}
+
+/// `items` in the order of the spelling of their keys, rather than that of the interner
+fn spelled<'a, K: KeyType, T>(items: impl Iterator<Item = (&'a K, T)>) -> Vec<(&'a K, T)> {
+    let mut items = items.collect::<Vec<_>>();
+    items.sort_by_cached_key(|(key, _)| key.to_string());
+    items
+}
+
+/// a compiled lexicon read from the front, with its symbols once read
+struct Reader<'a, K> {
+    bytes: &'a [u8],
+    symbols: Vec<K>,
+}
+
+impl<'a, K: KeyType> Reader<'a, K> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], CompileError> {
+        if self.bytes.len() < len {
+            return Err(CompileError::Truncated);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+    fn byte(&mut self) -> Result<u8, CompileError> {
+        Ok(self.take(1)?[0])
+    }
+    fn flag(&mut self) -> Result<bool, CompileError> {
+        match self.byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            tag => Err(CompileError::Tag(tag)),
+        }
+    }
+    fn u32(&mut self) -> Result<u32, CompileError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+    /// a string read in place
+    fn string(&mut self) -> Result<&'a str, CompileError> {
+        let len = self.u32()? as usize;
+        let bytes = self.take(len)?;
+        std::str::from_utf8(bytes).map_err(|_| CompileError::Utf8)
+    }
+    fn symbol(&mut self) -> Result<K, CompileError> {
+        let index = self.u32()?;
+        let symbol = self.symbols.get(index as usize);
+        symbol.cloned().ok_or(CompileError::Symbol(index))
+    }
+    fn option(&mut self) -> Result<Option<K>, CompileError> {
+        match self.byte()? {
+            0 => Ok(None),
+            1 => Ok(Some(self.symbol()?)),
+            tag => Err(CompileError::Tag(tag)),
+        }
+    }
+    fn features(&mut self) -> Result<FeatureSet<K>, CompileError> {
+        let mut fs = FeatureSet::new();
+        for _ in 0..self.u32()? {
+            let category = self.symbol()?;
+            fs.insert(category, self.option()?);
+        }
+        for _ in 0..self.u32()? {
+            let category = self.symbol()?;
+            fs.insert_variable(category, self.u32()? as usize);
+        }
+        for _ in 0..self.u32()? {
+            let category = self.symbol()?;
+            let constraint = match self.byte()? {
+                0 => Constraint::OneOf(self.symbols()?),
+                1 => Constraint::NoneOf(self.symbols()?),
+                2 => Constraint::Absent,
+                tag => return Err(CompileError::Tag(tag)),
+            };
+            fs.insert_constraint(category, constraint);
+        }
+        Ok(fs)
+    }
+    fn symbols<C: FromIterator<K>>(&mut self) -> Result<C, CompileError> {
+        let len = self.u32()?;
+        (0..len).map(|_| self.symbol()).collect()
+    }
+    fn value(&mut self) -> Result<SyntaxValue<K>, CompileError> {
+        match self.byte()? {
+            0 => Ok(SyntaxValue::Item(self.symbol()?)),
+            1 => Ok(SyntaxValue::Features(self.features()?)),
+            tag => Err(CompileError::Tag(tag)),
+        }
+    }
+    fn node(&mut self) -> Result<LexiconNode<K>, CompileError> {
+        match self.byte()? {
+            0 => Ok(LexiconNode::Value {
+                value: self.value()?,
+            }),
+            1 => Ok(LexiconNode::Lambda {
+                from: Box::new(self.node()?),
+                to: Box::new(self.node()?),
+                project: self.flag()?,
+            }),
+            2 => Ok(LexiconNode::Moved {
+                from: self.features()?,
+            }),
+            tag => Err(CompileError::Tag(tag)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interner::GlobalKey;
+    use crate::lexicon::parser::{LexiconParser, PestLexiconParser};
+    use crate::lexicon::serialize;
+
+    #[test]
+    fn compile_round_trip() {
+        let input = include_str!("../../assets/lexicons/en.lexicon");
+        let mut lexicon: SimpleLexicon<GlobalKey> = SimpleLexicon::new();
+        PestLexiconParser::parse_str(&mut lexicon, input).unwrap();
+        let compiled = compile(&lexicon);
+
+        let mut decoded: SimpleLexicon<GlobalKey> = SimpleLexicon::new();
+        decode(&compiled, &mut decoded).unwrap();
+        assert_eq!(serialize(&decoded), serialize(&lexicon));
+
+        // a file of another version of the format is rejected rather than misread
+        let mut outdated = compiled.clone();
+        outdated[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&0u32.to_le_bytes());
+        let result = decode(&outdated, &mut SimpleLexicon::<GlobalKey>::new());
+        assert!(matches!(result, Err(CompileError::Version(0))));
+        let result = decode(
+            &compiled[..compiled.len() - 1],
+            &mut SimpleLexicon::<GlobalKey>::new(),
+        );
+        assert!(matches!(result, Err(CompileError::Truncated)));
+
+        // the same lexicon compiles to the same bytes, whatever was interned before it
+        let mut reloaded: SimpleLexicon<GlobalKey> = SimpleLexicon::new();
+        PestLexiconParser::parse_str(&mut reloaded, input).unwrap();
+        assert_eq!(compile(&reloaded), compiled);
+        assert_eq!(compile(&decoded), compiled);
+    }
+}
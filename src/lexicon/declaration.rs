@@ -0,0 +1,8 @@
+/// a feature declared in `[Features]`: a value of a category, under its supertype if it has
+/// one, or a category declared without values
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Declaration<K> {
+    pub category: K,
+    pub value: Option<K>,
+    pub supertype: Option<K>,
+}
@@ -0,0 +1,59 @@
+use super::explain;
+use super::parser::{Diagnostic, KeyType};
+use std::collections::BTreeSet;
+
+/// entries standing for more concrete entries are compared as they are written
+const MAX_EXPANSION: usize = 1024;
+
+/// the concrete entries of `new` that `old` does not have, and those `old` has that `new`
+/// does not, so that renaming a variable or reordering lines makes no difference
+pub fn diff<K: KeyType>(
+    old: &str,
+    new: &str,
+) -> Result<(Vec<String>, Vec<String>), Vec<Diagnostic>> {
+    let old = expanded::<K>(old)?;
+    let new = expanded::<K>(new)?;
+    let added = new.difference(&old).cloned().collect();
+    let removed = old.difference(&new).cloned().collect();
+    Ok((added, removed))
+}
+
+fn expanded<K: KeyType>(input: &str) -> Result<BTreeSet<String>, Vec<Diagnostic>> {
+    let mut entries = BTreeSet::new();
+    for explanation in explain::<K>(input, MAX_EXPANSION)? {
+        match explanation.expansion.is_empty() {
+            true => entries.extend(explanation.loaded),
+            false => entries.extend(explanation.expansion.into_iter().map(|(_, entry)| entry)),
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interner::GlobalKey;
+
+    #[test]
+    fn diff_expanded_entries() {
+        let old = "[Features]
+number = sg, pl
+[Functional]
+DetH-number = (NounP-number > DetP-number)
+[Lexical]
+the = DetH
+";
+        // the same entries, but `pl` only, and a new item
+        let new = "[Features]
+number = sg, pl
+[Functional]
+DetH-pl = (NounP-pl > DetP-pl)
+[Lexical]
+a = DetH
+the = DetH
+";
+        let (added, removed) = diff::<GlobalKey>(old, new).unwrap();
+        assert_eq!(added, vec!["a = DetH"]);
+        assert_eq!(removed, vec!["DetH-sg = (NounP-sg > DetP-sg)"]);
+    }
+}
@@ -1,21 +1,66 @@
 use super::LexiconNode;
-use crate::syntax::FeatureSet;
+use crate::syntax::{Bindings, FeatureSet, Variable};
 use std::fmt::Display;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum LexiconEntry<K> {
-    Lexical(LexiconNode<K>),
+    /// the node of a token, and the lemma it is a form of, if it is looked up by one
+    Lexical(LexiconNode<K>, Option<K>),
+    /// the node of a token not in the lexicon, guessed from `[Unknown]`
+    Guess(LexiconNode<K>),
     Functional {
         to: LexiconNode<K>,
         project: Option<FeatureSet<K>>,
+        /// the features this entry was looked up by
+        key: FeatureSet<K>,
     },
 }
 
+impl<K: Clone + Ord> LexiconEntry<K> {
+    fn features_mut(&mut self) -> Vec<&mut FeatureSet<K>> {
+        match self {
+            LexiconEntry::Lexical(node, _) | LexiconEntry::Guess(node) => node.features_mut(),
+            LexiconEntry::Functional { to, project, key } => {
+                let mut features = to.features_mut();
+                features.extend(project);
+                features.push(key);
+                features
+            }
+        }
+    }
+
+    /// renames the variables of the entry apart from those below `offset`
+    pub fn offset_variables(&mut self, offset: Variable) {
+        for fs in self.features_mut() {
+            fs.offset_variables(offset);
+        }
+    }
+
+    /// unifies the key with the features the entry is applied to, binding the variables of
+    /// the entry in place and returning the bindings for the variables of `query`
+    pub fn bind(&mut self, query: &FeatureSet<K>) -> Option<Bindings<K>> {
+        let mut bindings = Bindings::new();
+        if let LexiconEntry::Functional { key, .. } = self {
+            if !key.unify(query, &mut bindings) {
+                return None;
+            }
+            for fs in self.features_mut() {
+                fs.substitute(&bindings);
+            }
+        }
+        Some(bindings)
+    }
+}
+
 impl<K: Display> Display for LexiconEntry<K> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            LexiconEntry::Lexical(node) => write!(f, "token = {}", node),
-            LexiconEntry::Functional { to, project: _ } => write!(f, "interpretation = {}", to),
+            LexiconEntry::Lexical(node, None) => write!(f, "token = {}", node),
+            LexiconEntry::Lexical(node, Some(lemma)) => {
+                write!(f, "token = {} ({})", node, lemma)
+            }
+            LexiconEntry::Guess(node) => write!(f, "guess = {}", node),
+            LexiconEntry::Functional { to, .. } => write!(f, "interpretation = {}", to),
         }
     }
 }
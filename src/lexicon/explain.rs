@@ -0,0 +1,227 @@
+use super::parser::{Diagnostic, KeyType, LexiconParser, PestLexiconParser, Symbol, SymbolKind};
+use super::serialize::{features, node_source};
+use super::{Lexicon, LexiconNode, SimpleLexicon};
+use crate::syntax::{Bindings, FeatureSet, SyntaxValue, Variable};
+use std::collections::BTreeMap;
+
+/// what an entry of a lexicon is loaded as, and the concrete entries it stands for
+#[derive(Debug)]
+pub struct Explanation {
+    /// the line the entry starts at, from 1
+    pub line: usize,
+    pub source: String,
+    pub loaded: Vec<String>,
+    /// the number of concrete entries, one per value of each variable
+    pub size: usize,
+    /// each concrete entry with the values bound to the variables, unless there are more
+    /// than asked for
+    pub expansion: Vec<(String, String)>,
+}
+
+/// explains every entry of `input`, expanding those standing for at most `max` entries
+pub fn explain<K: KeyType>(input: &str, max: usize) -> Result<Vec<Explanation>, Vec<Diagnostic>> {
+    let mut lexicon: SimpleLexicon<K> = SimpleLexicon::new();
+    PestLexiconParser::parse_str(&mut lexicon, input)?;
+    let sections = PestLexiconParser::outline(input).map_err(|diagnostic| vec![diagnostic])?;
+
+    let mut explanations = Vec::new();
+    for section in sections
+        .iter()
+        .filter(|section| matches!(section.name.as_str(), "[Functional]" | "[Lexical]"))
+    {
+        for entry in &section.children {
+            let lexicon = load_entry::<K>(input, &sections, entry)?;
+            let mut loaded = Vec::new();
+            let mut size = 0;
+            let mut expansion = Vec::new();
+            for (from, node) in lexicon.entries() {
+                loaded.push(source(&from, &node));
+                let variables = variables(&lexicon, &from, &node);
+                let entry_size = variables.values().map(Vec::len).product::<usize>();
+                size += entry_size;
+                if entry_size <= max {
+                    expansion.extend(expand(&from, &node, &variables));
+                }
+            }
+            if size > max {
+                expansion.clear();
+            }
+            explanations.push(Explanation {
+                line: entry.start.0,
+                source: lines(input, entry).trim_end().to_string(),
+                loaded,
+                size,
+                expansion,
+            });
+        }
+    }
+    Ok(explanations)
+}
+
+/// the entry `entry` of `input` loaded alone, with the features declared in `sections`
+pub fn load_entry<K: KeyType>(
+    input: &str,
+    sections: &[Symbol],
+    entry: &Symbol,
+) -> Result<SimpleLexicon<K>, Vec<Diagnostic>> {
+    let header = match entry.kind {
+        SymbolKind::Functional => "[Functional]",
+        _ => "[Lexical]",
+    };
+    let mut alone = String::from("[Features]\n");
+    let features = sections
+        .iter()
+        .filter(|section| section.name == "[Features]");
+    for category in features.flat_map(|section| &section.children) {
+        alone.push_str(&lines(input, category));
+    }
+    alone.push_str(header);
+    alone.push('\n');
+    alone.push_str(&lines(input, entry));
+
+    let mut lexicon = SimpleLexicon::new();
+    PestLexiconParser::parse_str(&mut lexicon, &alone)?;
+    Ok(lexicon)
+}
+
+/// the source lines `symbol` spans, each ending with a newline
+fn lines(input: &str, symbol: &Symbol) -> String {
+    let lines = input.lines().skip(symbol.start.0 - 1);
+    let lines = lines.take(symbol.end.0 - symbol.start.0 + 1);
+    lines.map(|line| format!("{line}\n")).collect()
+}
+
+fn source<K: KeyType>(from: &SyntaxValue<K>, node: &LexiconNode<K>) -> String {
+    match from {
+        SyntaxValue::Features(key) => format!("{} = {}", features(key), node_source(node)),
+        SyntaxValue::Item(item) => format!("{} = {}", item, node_source(node)),
+    }
+}
+
+/// the values each variable of the entry may be bound to, among the declared values without
+/// subtypes of its category
+fn variables<K: KeyType>(
+    lexicon: &SimpleLexicon<K>,
+    from: &SyntaxValue<K>,
+    node: &LexiconNode<K>,
+) -> BTreeMap<Variable, Vec<(K, K)>> {
+    let declarations = lexicon.declarations();
+    let is_supertype = |value: &K| {
+        declarations
+            .iter()
+            .any(|d| d.supertype.as_ref() == Some(value))
+    };
+
+    let mut fsets = node.features();
+    if let SyntaxValue::Features(key) = from {
+        fsets.push(key);
+    }
+    // the constraint of a variable is written with one of its uses, e.g., in the key
+    let mut constrained = BTreeMap::new();
+    for fs in fsets {
+        for category in fs.categories() {
+            if let Some(variable) = fs.get_variable(category) {
+                let (_, constraint) = constrained
+                    .entry(variable)
+                    .or_insert((category.clone(), None));
+                if constraint.is_none() {
+                    *constraint = fs.get_constraint(category);
+                }
+            }
+        }
+    }
+
+    let mut variables = BTreeMap::new();
+    for (variable, (category, constraint)) in constrained {
+        let allows = |value: &K| constraint.is_none_or(|c| c.allows(&Some(value.clone())));
+        let declared = declarations.iter().filter(|d| d.category == category);
+        let values = declared
+            .filter_map(|d| d.value.clone())
+            .filter(|value| !is_supertype(value) && allows(value))
+            .map(|value| (category.clone(), value));
+        variables.insert(variable, values.collect());
+    }
+    variables
+}
+
+/// the entry once for each combination of the values of its variables
+fn expand<K: KeyType>(
+    from: &SyntaxValue<K>,
+    node: &LexiconNode<K>,
+    variables: &BTreeMap<Variable, Vec<(K, K)>>,
+) -> Vec<(String, String)> {
+    let mut combinations = vec![Vec::new()];
+    for (&variable, values) in variables {
+        let extended = combinations.iter().flat_map(|combination: &Vec<_>| {
+            values.iter().map(move |value| {
+                let mut combination = combination.clone();
+                combination.push((variable, value.clone()));
+                combination
+            })
+        });
+        combinations = extended.collect();
+    }
+
+    let combinations = combinations.into_iter();
+    combinations
+        .map(|combination| {
+            let mut bindings = Bindings::new();
+            let mut bound = Vec::new();
+            for (variable, (category, value)) in combination {
+                bound.push(format!("{category}={value}"));
+                bindings.bind_value(variable, &Some(value));
+            }
+            let mut node = node.clone();
+            node.substitute(&bindings);
+            let from = match from {
+                SyntaxValue::Features(key) => SyntaxValue::Features(substitute(key, &bindings)),
+                item => item.clone(),
+            };
+            (bound.join(", "), source(&from, &node))
+        })
+        .collect()
+}
+
+/// `fs` with its bound variables replaced, and the constraints they met dropped
+fn substitute<K: KeyType>(fs: &FeatureSet<K>, bindings: &Bindings<K>) -> FeatureSet<K> {
+    let mut fs = fs.clone();
+    fs.substitute(bindings);
+    fs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interner::GlobalKey;
+
+    #[test]
+    fn explain_entries() {
+        let input = "[Features]
+tense = past, present
+person = local{1st, 2nd}, 3rd
+[Functional]
+VerbH-tense-person!=3rd = (DetP-person > TenseP-tense)
+[Lexical]
+ate = VerbH-past
+";
+        let explanations = explain::<GlobalKey>(input, 4).unwrap();
+        assert_eq!(explanations.len(), 2);
+        let verb = &explanations[0];
+        assert_eq!(verb.line, 5);
+        assert_eq!(verb.size, 4);
+        assert_eq!(
+            verb.expansion[0],
+            (
+                "tense=past, person=1st".to_string(),
+                "VerbH-1st-past = (DetP-1st > TenseP-past)".to_string()
+            )
+        );
+        assert_eq!(explanations[1].loaded, vec!["ate = VerbH-past"]);
+        assert_eq!(explanations[1].size, 1);
+
+        // too many to expand
+        let explanations = explain::<GlobalKey>(input, 3).unwrap();
+        assert_eq!(explanations[0].size, 4);
+        assert!(explanations[0].expansion.is_empty());
+    }
+}
@@ -1,10 +1,25 @@
+mod check;
+mod compile;
+mod declaration;
+mod diff;
 mod entry;
+mod explain;
+mod morphology;
 mod node;
 pub mod parser;
+mod serialize;
 mod simple;
+pub mod suggest;
 mod r#trait;
 
+pub use check::check;
+pub use compile::{CompileError, VERSION, compile, open};
+pub use declaration::Declaration;
+pub use diff::diff;
 pub use entry::LexiconEntry;
+pub use explain::{explain, load_entry};
+pub use morphology::{Paradigm, SuffixRule, inflect};
 pub use node::LexiconNode;
-pub use simple::SimpleLexicon;
+pub use serialize::serialize;
+pub use simple::{KeyedNode, SimpleLexicon};
 pub use r#trait::Lexicon;
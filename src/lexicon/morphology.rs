@@ -0,0 +1,115 @@
+use super::LexiconNode;
+use super::serialize::features;
+use crate::syntax::{FeatureSet, SyntaxValue};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// a regular inflection, e.g., `-ed = VerbH-past`: a word ending with `suffix` stands for
+/// its stem with the values of `features`, if the stem has each of its categories
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuffixRule<K> {
+    pub suffix: String,
+    pub features: FeatureSet<K>,
+}
+
+impl<K: Clone + Ord> SuffixRule<K> {
+    /// the item `word` is an inflection of, if it ends with the suffix and what is left of
+    /// it is spelled like an item of the lexicon, e.g., not `bu` for `bus` with `-s`
+    pub fn stem<'a>(&self, word: &str, spellings: &'a HashMap<String, K>) -> Option<&'a K> {
+        let stem = word.strip_suffix(&self.suffix)?;
+        match stem.is_empty() {
+            true => None,
+            false => spellings.get(stem),
+        }
+    }
+
+    /// an entry of the stem with the values of the rule, unless they conflict with it
+    pub fn inflect(&self, stem: &LexiconNode<K>) -> Option<LexiconNode<K>> {
+        inflect(&self.features, stem)
+    }
+}
+
+/// the forms of a lemma that are not regular, e.g., `eat: past=ate, present-3rd=eats`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paradigm<K> {
+    pub lemma: K,
+    /// the values of each form, with the form
+    pub cells: Vec<(FeatureSet<K>, K)>,
+}
+
+/// `stem` with the values of `features`, if it has each of their categories and none of
+/// the values conflicts with it
+pub fn inflect<K: Clone + Ord>(
+    features: &FeatureSet<K>,
+    stem: &LexiconNode<K>,
+) -> Option<LexiconNode<K>> {
+    let LexiconNode::Value {
+        value: SyntaxValue::Features(fs),
+    } = stem
+    else {
+        return None;
+    };
+    let mut categories = features.iter().filter(|(_, value)| value.is_none());
+    if !categories.all(|(category, _)| fs.contains_key_value(category, &None)) {
+        return None;
+    }
+    let mut inflected = fs.clone();
+    FeatureSet::project(features, &mut inflected, &FeatureSet::new()).ok()?;
+    Some(LexiconNode::Value {
+        value: SyntaxValue::Features(inflected),
+    })
+}
+
+impl<K: Display + Clone + Ord> Display for SuffixRule<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "-{} = {}", self.suffix, features(&self.features))
+    }
+}
+
+impl<K: Display + Clone + Ord> Display for Paradigm<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cells = self.cells.iter();
+        let cells = cells.map(|(fs, form)| format!("{}={}", features(fs), form));
+        write!(
+            f,
+            "{}: {}",
+            self.lemma,
+            cells.collect::<Vec<_>>().join(", ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inflect_stem() {
+        let value = |features: &[(&'static str, Option<&'static str>)]| {
+            let mut fs = FeatureSet::new();
+            for (category, value) in features {
+                fs.insert(*category, *value);
+            }
+            fs
+        };
+        let node = |fs| LexiconNode::Value {
+            value: SyntaxValue::Features(fs),
+        };
+        let rule = SuffixRule {
+            suffix: "ed".to_string(),
+            features: value(&[("VerbH", None), ("tense", Some("past"))]),
+        };
+        let spellings = HashMap::from([("jump".to_string(), "jump"), (String::new(), "")]);
+        assert_eq!(rule.stem("jumped", &spellings), Some(&"jump"));
+        assert_eq!(rule.stem("ed", &spellings), None);
+        assert_eq!(rule.stem("walked", &spellings), None);
+
+        let jump = node(value(&[("VerbH", None)]));
+        let jumped = node(value(&[("VerbH", None), ("tense", Some("past"))]));
+        assert_eq!(rule.inflect(&jump), Some(jumped));
+        // not a verb, or already in another tense
+        assert_eq!(rule.inflect(&node(value(&[("NounP", None)]))), None);
+        let present = value(&[("VerbH", None), ("tense", Some("present"))]);
+        assert_eq!(rule.inflect(&node(present)), None);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::syntax::{FeatureSet, SyntaxValue};
+use crate::syntax::{Bindings, FeatureSet, SyntaxValue};
 use std::fmt::Display;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -16,6 +16,59 @@ pub enum LexiconNode<K> {
     },
 }
 
+impl<K> LexiconNode<K> {
+    /// the features this node ends up as, e.g., `C` for `(A > (B > C))`
+    pub fn get_features_right(&self) -> Option<&FeatureSet<K>> {
+        match self {
+            LexiconNode::Value {
+                value: SyntaxValue::Features(fs),
+            } => Some(fs),
+            LexiconNode::Value { .. } => None,
+            LexiconNode::Lambda { to, .. } => to.get_features_right(),
+            LexiconNode::Moved { from } => Some(from),
+        }
+    }
+
+    /// every feature set in this node
+    pub fn features(&self) -> Vec<&FeatureSet<K>> {
+        match self {
+            LexiconNode::Value {
+                value: SyntaxValue::Features(fs),
+            } => vec![fs],
+            LexiconNode::Value { .. } => Vec::new(),
+            LexiconNode::Lambda { from, to, .. } => {
+                let mut features = from.features();
+                features.extend(to.features());
+                features
+            }
+            LexiconNode::Moved { from } => vec![from],
+        }
+    }
+
+    pub fn features_mut(&mut self) -> Vec<&mut FeatureSet<K>> {
+        match self {
+            LexiconNode::Value {
+                value: SyntaxValue::Features(fs),
+            } => vec![fs],
+            LexiconNode::Value { .. } => Vec::new(),
+            LexiconNode::Lambda { from, to, .. } => {
+                let mut features = from.features_mut();
+                features.extend(to.features_mut());
+                features
+            }
+            LexiconNode::Moved { from } => vec![from],
+        }
+    }
+}
+
+impl<K: Clone + Ord> LexiconNode<K> {
+    pub fn substitute(&mut self, bindings: &Bindings<K>) {
+        for fs in self.features_mut() {
+            fs.substitute(bindings);
+        }
+    }
+}
+
 impl<K> Display for LexiconNode<K>
 where
     K: Display,
@@ -1,5 +1,9 @@
+mod diagnostic;
 mod pest;
+mod symbol;
 mod r#trait;
 
-pub use pest::PestLexiconParser;
+pub use diagnostic::{Diagnostic, ParseResult, Severity};
+pub use pest::{KeyType, PestLexiconParser};
+pub use symbol::{Symbol, SymbolKind};
 pub use r#trait::LexiconParser;
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt::Debug;
 use std::hash::Hash;
 
@@ -6,6 +6,10 @@ use std::hash::Hash;
 pub struct FeatureTopology<K> {
     cat_map: HashMap<K, HashSet<K>>,
     val_map: HashMap<K, K>,
+    /// the values directly under a value, e.g., `1st` and `2nd` under `local`
+    sub_map: HashMap<K, Vec<K>>,
+    /// categories declared without values, e.g., syntactic categories in strict mode
+    bare: HashSet<K>,
 }
 
 impl<K> FeatureTopology<K> {
@@ -13,6 +17,8 @@ impl<K> FeatureTopology<K> {
         FeatureTopology {
             cat_map: HashMap::new(),
             val_map: HashMap::new(),
+            sub_map: HashMap::new(),
+            bare: HashSet::new(),
         }
     }
 }
@@ -33,11 +39,84 @@ where
         self.cat_map.contains_key(category)
     }
 
-    pub fn get_from_category(&self, category: &K) -> Option<&HashSet<K>> {
-        self.cat_map.get(category)
+    /// `category` is declared, but has no values
+    pub fn insert_bare(&mut self, category: K) {
+        self.bare.insert(category);
+    }
+
+    /// a category, with or without values, or a value
+    pub fn is_declared(&self, feature: &K) -> bool {
+        self.is_category(feature)
+            || self.bare.contains(feature)
+            || self.val_map.contains_key(feature)
+    }
+
+    /// every category, with or without values, and every value
+    pub fn declared(&self) -> impl Iterator<Item = &K> {
+        let categories = self.cat_map.keys().chain(self.bare.iter());
+        categories.chain(self.val_map.keys())
+    }
+
+    pub fn categories(&self) -> impl Iterator<Item = &K> {
+        self.cat_map.keys()
+    }
+
+    pub fn values(&self, category: &K) -> impl Iterator<Item = &K> {
+        self.cat_map.get(category).into_iter().flatten()
     }
 
     pub fn get_from_value(&self, value: &K) -> Option<K> {
         self.val_map.get(value).cloned()
     }
+
+    /// `value` is declared under `supertype` within the same category
+    pub fn insert_subtype(&mut self, supertype: K, value: K) {
+        self.sub_map.entry(supertype).or_default().push(value);
+    }
+
+    pub fn is_supertype(&self, value: &K) -> bool {
+        self.sub_map.contains_key(value)
+    }
+}
+
+impl<K> FeatureTopology<K>
+where
+    K: Clone + Ord + Hash,
+{
+    /// the values without subtypes that `value` stands for, `value` itself if it has none
+    pub fn get_leaves(&self, value: &K) -> BTreeSet<K> {
+        match self.sub_map.get(value) {
+            Some(subtypes) => subtypes
+                .iter()
+                .flat_map(|sub| self.get_leaves(sub))
+                .collect(),
+            None => BTreeSet::from([value.clone()]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_of_supertype() {
+        // person = local{1st, incl{2nd, 4th}}, 3rd
+        let mut ft = FeatureTopology::new();
+        for value in ["local", "1st", "incl", "2nd", "4th", "3rd"] {
+            ft.insert("person", value);
+        }
+        ft.insert_subtype("local", "1st");
+        ft.insert_subtype("local", "incl");
+        ft.insert_subtype("incl", "2nd");
+        ft.insert_subtype("incl", "4th");
+
+        assert_eq!(
+            ft.get_leaves(&"local"),
+            BTreeSet::from(["1st", "2nd", "4th"])
+        );
+        assert_eq!(ft.get_leaves(&"3rd"), BTreeSet::from(["3rd"]));
+        assert!(ft.is_supertype(&"incl"));
+        assert_eq!(ft.get_from_value(&"incl"), Some("person"));
+    }
 }
@@ -0,0 +1,191 @@
+use super::super::Diagnostic;
+use super::r#struct::{PestLexiconParser, Rule, syntax_error};
+
+use pest::Parser;
+use pest::iterators::Pair;
+
+/// a line of a lexicon, as it is laid out
+enum Line {
+    /// a section header, a comment or a blank line, kept as it is
+    Verbatim(String),
+    Entry {
+        from: String,
+        to: Option<String>,
+        comment: Option<String>,
+    },
+}
+
+impl PestLexiconParser {
+    /// the lexicon laid out canonically: single spaces between the parts of an entry, `=`
+    /// aligned within each run of entries, and comments, sections and their order kept
+    pub fn format(input: &str) -> Result<String, Diagnostic> {
+        PestLexiconParser::parse(Rule::lexicon, input).map_err(syntax_error)?;
+
+        let mut rule = Rule::feature_entry;
+        let mut lines = Vec::new();
+        for line in input.lines() {
+            let (code, comment) = split_comment(line);
+            let code = code.trim();
+            if code.is_empty() || code.starts_with('[') {
+                rule = match code {
+                    "[Features]" => Rule::feature_entry,
+                    "[Morphology]" => Rule::morphology_entry,
+                    "[Functional]" => Rule::functional_entry,
+                    "[Lexical]" => Rule::lexical_entry,
+                    "[Paradigms]" => Rule::paradigm_entry,
+                    "[Unknown]" => Rule::unknown_entry,
+                    _ => rule,
+                };
+                let code = Some(code).filter(|code| !code.is_empty());
+                let line = [code, comment].into_iter().flatten();
+                lines.push(Line::Verbatim(line.collect::<Vec<_>>().join(" ")));
+                continue;
+            }
+            // the whole lexicon parses, and entries take a line each
+            let pair = PestLexiconParser::parse(rule, code).map_err(syntax_error)?;
+            let pair = pair.into_iter().next().expect("Formatting: no entry");
+            // the cells of a paradigm are not aligned on `=`
+            if rule == Rule::paradigm_entry {
+                lines.push(Line::Entry {
+                    from: render(pair),
+                    to: None,
+                    comment: comment.map(str::to_string),
+                });
+                continue;
+            }
+            let mut inner = pair.into_inner();
+            let from = inner.next().expect("Formatting entry: no from");
+            lines.push(Line::Entry {
+                from: render(from),
+                to: inner.next().map(render),
+                comment: comment.map(str::to_string),
+            });
+        }
+        Ok(layout(lines))
+    }
+}
+
+/// the code and the comment of a line
+fn split_comment(line: &str) -> (&str, Option<&str>) {
+    match line.find('#') {
+        Some(index) => (&line[..index], Some(line[index..].trim_end())),
+        None => (line, None),
+    }
+}
+
+fn render(pair: Pair<Rule>) -> String {
+    let rule = pair.as_rule();
+    let text = pair.as_str().trim().to_string();
+    let inner = pair.into_inner().map(render).collect::<Vec<_>>();
+    match rule {
+        Rule::lambda => format!("({} {} {})", inner[0], inner[1], inner[2]),
+        Rule::moved => format!("MOVED({})", inner[0]),
+        Rule::feature_set => inner.join("-"),
+        Rule::absent_feature => format!("!{}", inner[0]),
+        Rule::one_of_feature => format!("{}={}", inner[0], inner[1]),
+        Rule::none_of_feature => format!("{}!={}", inner[0], inner[1]),
+        Rule::feature_alternatives => inner.join("|"),
+        Rule::paradigm_entry => format!("{}: {}", inner[0], inner[1..].join(", ")),
+        Rule::paradigm_cell => format!("{}={}", inner[0], inner[1]),
+        Rule::cell_features => inner.join("-"),
+        Rule::feature_value_set => inner.join(", "),
+        Rule::feature_value_item => match inner.as_slice() {
+            [value, subtypes] => format!("{value}{{{subtypes}}}"),
+            _ => inner.concat(),
+        },
+        _ => text,
+    }
+}
+
+/// entries aligned on `=` within runs of entries, and runs of blank lines made one
+fn layout(lines: Vec<Line>) -> String {
+    let mut formatted = Vec::new();
+    let mut lines = lines.into_iter().peekable();
+    while let Some(line) = lines.next() {
+        let mut run = vec![line];
+        while matches!(run.last(), Some(Line::Entry { .. }))
+            && matches!(lines.peek(), Some(Line::Entry { .. }))
+        {
+            run.extend(lines.next());
+        }
+        let width = run
+            .iter()
+            .filter_map(|line| match line {
+                Line::Entry {
+                    from, to: Some(_), ..
+                } => Some(from.chars().count()),
+                _ => None,
+            })
+            .max()
+            .unwrap_or_default();
+        for line in run {
+            formatted.push(match line {
+                Line::Verbatim(text) => text,
+                Line::Entry { from, to, comment } => {
+                    let mut text = match to {
+                        Some(to) => format!("{from:width$} = {to}"),
+                        None => from,
+                    };
+                    if let Some(comment) = comment {
+                        text = format!("{text} {comment}");
+                    }
+                    text
+                }
+            });
+        }
+    }
+
+    let mut output = String::new();
+    let mut blank = true;
+    for line in formatted {
+        if line.is_empty() && blank {
+            continue;
+        }
+        blank = line.is_empty();
+        output.push_str(&line);
+        output.push('\n');
+    }
+    while output.ends_with("\n\n") {
+        output.pop();
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_lexicon() {
+        let input = "[Features]
+number=sg,pl
+person = local { 1st , 2nd },3rd
+
+
+[Functional]
+# Det
+DetH-number = ( NounP-number>DetP-number )   # agreement
+DetP = DetP-case!=obj|nocase
+
+[Lexical]
+?=Punct-question
+";
+        let formatted = PestLexiconParser::format(input).unwrap();
+        assert_eq!(
+            formatted,
+            "[Features]
+number = sg, pl
+person = local{1st, 2nd}, 3rd
+
+[Functional]
+# Det
+DetH-number = (NounP-number > DetP-number) # agreement
+DetP        = DetP-case!=obj|nocase
+
+[Lexical]
+? = Punct-question
+"
+        );
+        assert_eq!(PestLexiconParser::format(&formatted).unwrap(), formatted);
+    }
+}
@@ -3,11 +3,11 @@ use std::hash::Hash;
 use std::str::FromStr;
 
 pub trait KeyType:
-    Clone + Copy + Debug + Display + FromStr + PartialEq + Eq + PartialOrd + Ord + Hash
+    Clone + Debug + Display + FromStr + PartialEq + Eq + PartialOrd + Ord + Hash
 {
 }
 
-impl<K: Clone + Copy + Debug + Display + FromStr + PartialEq + Eq + PartialOrd + Ord + Hash> KeyType
+impl<K: Clone + Debug + Display + FromStr + PartialEq + Eq + PartialOrd + Ord + Hash> KeyType
     for K
 {
 }
@@ -1,5 +1,8 @@
 mod feature_topology;
+mod format;
 mod key_type;
+mod outline;
 mod r#struct;
 
+pub use key_type::KeyType;
 pub use r#struct::PestLexiconParser;
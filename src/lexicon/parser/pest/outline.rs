@@ -0,0 +1,120 @@
+use super::super::{Symbol, SymbolKind};
+use super::r#struct::{PestLexiconParser, Rule, syntax_error};
+use crate::lexicon::parser::Diagnostic;
+
+use pest::Parser;
+use pest::iterators::Pair;
+
+impl PestLexiconParser {
+    /// the sections of a lexicon with the entries within them, and the features declared
+    /// in `[Features]` with their values, as written
+    pub fn outline(input: &str) -> Result<Vec<Symbol>, Diagnostic> {
+        let pairs = PestLexiconParser::parse(Rule::lexicon, input).map_err(syntax_error)?;
+        let sections = pairs.filter(|pair| pair.as_rule() != Rule::EOI);
+        Ok(sections.map(outline_section).collect())
+    }
+}
+
+fn outline_section(pair: Pair<Rule>) -> Symbol {
+    let (start, end) = span(&pair);
+    let name = match pair.as_rule() {
+        Rule::feature_section => "[Features]",
+        Rule::morphology_section => "[Morphology]",
+        Rule::functional_section => "[Functional]",
+        Rule::lexical_section => "[Lexical]",
+        Rule::paradigm_section => "[Paradigms]",
+        Rule::unknown_section => "[Unknown]",
+        r => unreachable!("Outlining sections: unexpected rule: {r:?}"),
+    };
+    let children = pair.into_inner().map(outline_entry).collect::<Vec<_>>();
+    Symbol {
+        name: name.to_string(),
+        kind: SymbolKind::Section,
+        start,
+        end: children.last().map_or(end, |child| child.end),
+        children,
+    }
+}
+
+fn outline_entry(pair: Pair<Rule>) -> Symbol {
+    let kind = match pair.as_rule() {
+        Rule::feature_entry => SymbolKind::Category,
+        Rule::morphology_entry => SymbolKind::Suffix,
+        Rule::functional_entry => SymbolKind::Functional,
+        Rule::lexical_entry => SymbolKind::Lexical,
+        Rule::paradigm_entry => SymbolKind::Paradigm,
+        Rule::unknown_entry => SymbolKind::Guess,
+        r => unreachable!("Outlining entries: unexpected rule: {r:?}"),
+    };
+    let mut inner = pair.into_inner();
+    let pair_from = inner.next().expect("Outlining entry: no from");
+    let (start, mut end) = span(&pair_from);
+    let mut children = Vec::new();
+    if let Some(pair_to) = inner.next() {
+        end = span(&pair_to).1;
+        if kind == SymbolKind::Category {
+            children = outline_values(pair_to);
+        }
+    }
+    Symbol {
+        name: pair_from.as_str().trim().to_string(),
+        kind,
+        start,
+        end,
+        children,
+    }
+}
+
+/// the values of a category, with their subtypes within them
+fn outline_values(pair: Pair<Rule>) -> Vec<Symbol> {
+    let items = pair.into_inner().map(|pair_item| {
+        let mut inner = pair_item.into_inner();
+        let pair_val = inner.next().expect("Outlining feature value: no value");
+        let (start, end) = span(&pair_val);
+        Symbol {
+            name: pair_val.as_str().trim().to_string(),
+            kind: SymbolKind::Value,
+            start,
+            end,
+            children: inner.next().map(outline_values).unwrap_or_default(),
+        }
+    });
+    items.collect()
+}
+
+fn span(pair: &Pair<Rule>) -> ((usize, usize), (usize, usize)) {
+    let span = pair.as_span();
+    (span.start_pos().line_col(), span.end_pos().line_col())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outline_lexicon() {
+        let input = "[Features]
+person = local{1st, 2nd}, 3rd
+[Functional]
+DetP-local = (A > B)
+[Lexical]
+me = DetP-1st
+";
+        let sections = PestLexiconParser::outline(input).unwrap();
+        let names = sections.iter().map(|s| s.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["[Features]", "[Functional]", "[Lexical]"]);
+
+        let person = &sections[0].children[0];
+        assert_eq!((person.name.as_str(), person.start), ("person", (2, 1)));
+        let second = person.find("2nd").unwrap();
+        assert_eq!((second.start, second.end), ((2, 21), (2, 24)));
+
+        let entry = &sections[1].children[0];
+        assert_eq!(entry.kind, SymbolKind::Functional);
+        assert_eq!(
+            (entry.name.as_str(), entry.start, entry.end),
+            ("DetP-local", (4, 1), (4, 21))
+        );
+        assert_eq!(sections[2].children[0].name, "me");
+    }
+}
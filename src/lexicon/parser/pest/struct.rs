@@ -1,132 +1,378 @@
-use super::super::super::{Lexicon, LexiconNode};
-use super::super::LexiconParser;
+use super::super::super::suggest::closest;
+use super::super::super::{Declaration, Lexicon, LexiconNode, Paradigm, SimpleLexicon, SuffixRule};
+use super::super::{Diagnostic, LexiconParser, ParseResult, Severity};
 use super::feature_topology::FeatureTopology;
 use super::key_type::KeyType;
-use crate::syntax::{FeatureSet, SyntaxValue};
+use crate::syntax::{Constraint, FeatureSet, SyntaxValue, Variable};
 
 use pest::Parser;
+use pest::error::LineColLocation;
 use pest::iterators::{Pair, Pairs};
 use pest_derive::Parser;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 #[derive(Parser)]
 #[grammar = "lexicon/parser/pest/lexicon.pest"]
 pub struct PestLexiconParser;
 
 impl<K: KeyType> LexiconParser<K> for PestLexiconParser {
-    fn parse_str(lexicon: &mut impl Lexicon<K>, input: &str) -> Result<(), String> {
-        parse_lexicon(lexicon, input)
+    fn parse_str(lexicon: &mut impl Lexicon<K>, input: &str) -> ParseResult {
+        parse_lexicon(lexicon, input, false)
+    }
+    fn parse_str_strict(lexicon: &mut impl Lexicon<K>, input: &str) -> ParseResult {
+        parse_lexicon(lexicon, input, true)
+    }
+}
+
+/// the declared features entries are checked against, and what is wrong with them so far
+struct Loader<K> {
+    ft: FeatureTopology<K>,
+    strict: bool,
+    diagnostics: Vec<Diagnostic>,
+    /// the features declared so far, in order, to be kept by the lexicon
+    declarations: Vec<Declaration<K>>,
+}
+
+impl<K: KeyType> Loader<K> {
+    /// an undeclared feature is an error in strict mode, otherwise a warning if it is
+    /// close enough to a declared one to be a typo of it
+    fn check_declared(&mut self, pair: &Pair<Rule>, feature: &K) -> Result<(), Diagnostic> {
+        if self.ft.is_declared(feature) {
+            return Ok(());
+        }
+        let declared = self.ft.declared().map(K::to_string);
+        let suggestion = closest(&feature.to_string(), declared);
+        let message = format!(
+            "{feature} is not declared{}",
+            did_you_mean(suggestion.as_deref())
+        );
+        match (self.strict, suggestion) {
+            (true, _) => Err(diagnostic(pair, Severity::Error, message)),
+            (false, Some(_)) => {
+                self.diagnostics
+                    .push(diagnostic(pair, Severity::Warning, message));
+                Ok(())
+            }
+            (false, None) => Ok(()),
+        }
     }
 }
 
-fn parse_lexicon<K: KeyType>(lexicon: &mut impl Lexicon<K>, input: &str) -> Result<(), String> {
-    let pairs = PestLexiconParser::parse(Rule::lexicon, input).map_err(|e| e.to_string())?;
-    parse_sections(lexicon, pairs)
+/// entries with errors are skipped, so that every error of the lexicon is reported at once
+fn parse_lexicon<K: KeyType>(
+    lexicon: &mut impl Lexicon<K>,
+    input: &str,
+    strict: bool,
+) -> ParseResult {
+    let pairs =
+        PestLexiconParser::parse(Rule::lexicon, input).map_err(|e| vec![syntax_error(e)])?;
+    let mut loader = Loader {
+        ft: FeatureTopology::new(),
+        strict,
+        diagnostics: Vec::new(),
+        declarations: Vec::new(),
+    };
+    // loaded aside, so that a lexicon with errors leaves `lexicon` as it was
+    let mut loaded = SimpleLexicon::new();
+    parse_sections(&mut loaded, pairs, &mut loader);
+
+    let mut diagnostics = loader.diagnostics;
+    diagnostics.sort_by_key(|d| (d.line, d.column));
+    if diagnostics.iter().any(Diagnostic::is_error) {
+        return Err(diagnostics);
+    }
+    loaded
+        .declarations()
+        .into_iter()
+        .for_each(|d| lexicon.add_declaration(d));
+    loaded
+        .rules()
+        .into_iter()
+        .for_each(|rule| lexicon.add_rule(rule));
+    for (from, node) in loaded.entries() {
+        lexicon.add_entry(from, node);
+    }
+    loaded
+        .paradigms()
+        .into_iter()
+        .for_each(|p| lexicon.add_paradigm(p));
+    loaded
+        .unknown()
+        .into_iter()
+        .for_each(|node| lexicon.add_unknown(node));
+    Ok(diagnostics)
 }
 
 fn parse_sections<K: KeyType>(
     lexicon: &mut impl Lexicon<K>,
     pairs: Pairs<Rule>,
-) -> Result<(), String> {
+    loader: &mut Loader<K>,
+) {
     let mut feature_entries = Vec::new();
+    let mut morphology_entries = Vec::new();
     let mut functional_entries = Vec::new();
     let mut lexical_entries = Vec::new();
+    let mut paradigm_entries = Vec::new();
+    let mut unknown_entries = Vec::new();
 
     // collect all entries
     for pair in pairs {
         match pair.as_rule() {
             Rule::feature_section => feature_entries.extend(pair.into_inner()),
+            Rule::morphology_section => morphology_entries.extend(pair.into_inner()),
             Rule::functional_section => functional_entries.extend(pair.into_inner()),
             Rule::lexical_section => lexical_entries.extend(pair.into_inner()),
+            Rule::paradigm_section => paradigm_entries.extend(pair.into_inner()),
+            Rule::unknown_section => unknown_entries.extend(pair.into_inner()),
             Rule::EOI => (),
             r => unreachable!("Parsing sections: unexpected rule: {r:?}"),
         }
     }
 
     // parse feature entries into feature topology
-    let mut feature_topology: FeatureTopology<K> = FeatureTopology::new();
-    parse_feature_entries(&mut feature_topology, feature_entries)?;
+    for pair in feature_entries {
+        if let Err(diagnostic) = parse_feature_entry(loader, pair) {
+            loader.diagnostics.push(diagnostic);
+        }
+    }
+    for declaration in loader.declarations.drain(..) {
+        lexicon.add_declaration(declaration);
+    }
+
+    // parse suffix rules
+    for pair in morphology_entries {
+        match parse_morphology_entry(pair, loader) {
+            Ok(rule) => lexicon.add_rule(rule),
+            Err(diagnostic) => loader.diagnostics.push(diagnostic),
+        }
+    }
 
     // parse functional entries
-    parse_functional_entries(lexicon, functional_entries, &feature_topology)?;
+    for pair in functional_entries {
+        match parse_functional_entry(pair, loader) {
+            Ok((from, to)) => {
+                lexicon.add_entry(from, to);
+            }
+            Err(diagnostic) => loader.diagnostics.push(diagnostic),
+        }
+    }
 
     // parse lexical entries
-    parse_lexical_entries(lexicon, lexical_entries, &feature_topology)?;
+    for pair in lexical_entries {
+        match parse_lexical_entry(pair, loader) {
+            Ok((from, to)) => {
+                lexicon.add_entry(from, to);
+            }
+            Err(diagnostic) => loader.diagnostics.push(diagnostic),
+        }
+    }
 
-    Ok(())
+    // parse the nodes unknown tokens may be guessed to be
+    for pair in unknown_entries {
+        let pair_lnode = pair
+            .into_inner()
+            .next()
+            .expect("Parsing unknown entry: no node");
+        match parse_syntax_node(pair_lnode, loader, &mut VariableTable::new()) {
+            Ok(node) => lexicon.add_unknown(node),
+            Err(diagnostic) => loader.diagnostics.push(diagnostic),
+        }
+    }
+
+    // parse paradigms, whose lemmas are lexical entries by now
+    for pair in paradigm_entries {
+        match parse_paradigm_entry(pair, loader) {
+            Ok((pair_lemma, paradigm)) => {
+                let lemma = SyntaxValue::Item(paradigm.lemma.clone());
+                if lexicon.get_entries(&lemma).is_empty() {
+                    let message = format!("{} has no lexical entry to inflect", paradigm.lemma);
+                    let warning = diagnostic(&pair_lemma, Severity::Warning, message);
+                    loader.diagnostics.push(warning);
+                }
+                lexicon.add_paradigm(paradigm);
+            }
+            Err(diagnostic) => loader.diagnostics.push(diagnostic),
+        }
+    }
 }
 
-type SubstitutionTable<K> = HashMap<K, (K, Option<K>)>;
-type ParserState<T, K> = Vec<(T, SubstitutionTable<K>)>;
+/// the variable each category stands for within an entry
+type VariableTable<K> = HashMap<K, Variable>;
 
-fn parse_feature_entries<K: KeyType>(
-    ft: &mut FeatureTopology<K>,
-    pairs: Vec<Pair<Rule>>,
-) -> Result<(), String> {
-    for pair in pairs {
-        match pair.as_rule() {
-            Rule::feature_entry => {
-                let mut inner = pair.into_inner();
+fn parse_feature_entry<K: KeyType>(
+    loader: &mut Loader<K>,
+    pair: Pair<Rule>,
+) -> Result<(), Diagnostic> {
+    match pair.as_rule() {
+        Rule::feature_entry => {
+            let mut inner = pair.into_inner();
 
-                let pair_cat = inner.next().expect("Parsing feature entry: no category");
-                let category = parse_word(pair_cat)?;
+            let pair_cat = inner.next().expect("Parsing feature entry: no category");
+            let category = parse_word(&pair_cat)?;
+            check_undeclared(&loader.ft, &pair_cat, &category)?;
 
-                let pair_vals = inner.next().expect("Parsing feature entry: no values");
-                for pair_val in pair_vals.into_inner() {
-                    let val = parse_word(pair_val)?;
-                    ft.insert(category, val);
+            match inner.next() {
+                Some(pair_vals) => parse_feature_values(loader, category, None, pair_vals),
+                None => {
+                    loader.ft.insert_bare(category.clone());
+                    loader.declarations.push(Declaration {
+                        category,
+                        value: None,
+                        supertype: None,
+                    });
+                    Ok(())
                 }
             }
-            r => unreachable!("Parsing feature entries: unexpected rule: {r:?}"),
+        }
+        r => unreachable!("Parsing feature entries: unexpected rule: {r:?}"),
+    }
+}
+
+/// declares `person = local{1st, 2nd}, 3rd`, with `1st` and `2nd` as subtypes of `local`;
+/// a value declared twice is reported and skipped, with the rest of the values kept
+fn parse_feature_values<K: KeyType>(
+    loader: &mut Loader<K>,
+    category: K,
+    supertype: Option<K>,
+    pair: Pair<Rule>,
+) -> Result<(), Diagnostic> {
+    for pair_item in pair.into_inner() {
+        let mut inner = pair_item.into_inner();
+        let pair_val = inner.next().expect("Parsing feature value: no value");
+        let val = parse_word(&pair_val)?;
+        if val == category {
+            let message = format!("{val} is both a category and one of its values");
+            loader
+                .diagnostics
+                .push(diagnostic(&pair_val, Severity::Error, message));
+            continue;
+        }
+        if let Err(diagnostic) = check_undeclared(&loader.ft, &pair_val, &val) {
+            loader.diagnostics.push(diagnostic);
+            continue;
+        }
+        loader.ft.insert(category.clone(), val.clone());
+        if let Some(supertype) = &supertype {
+            loader.ft.insert_subtype(supertype.clone(), val.clone());
+        }
+        loader.declarations.push(Declaration {
+            category: category.clone(),
+            value: Some(val.clone()),
+            supertype: supertype.clone(),
+        });
+        if let Some(pair_subs) = inner.next() {
+            parse_feature_values(loader, category.clone(), Some(val), pair_subs)?;
         }
     }
     Ok(())
 }
 
-fn parse_functional_entries<K: KeyType>(
-    lexicon: &mut impl Lexicon<K>,
-    pairs: Vec<Pair<Rule>>,
+/// a category or value is declared once, as values are looked up without their category
+fn check_undeclared<K: KeyType>(
     ft: &FeatureTopology<K>,
-) -> Result<(), String> {
-    for pair in pairs {
-        match pair.as_rule() {
-            Rule::functional_entry => {
-                let mut inner = pair.into_inner();
-                let pair_fset = inner.next().expect("Parsing functional entry: no from");
-                let pair_lnode = inner.next().expect("Parsing functional entry: no to");
-
-                let subst = SubstitutionTable::new();
-                for (from, subst) in parse_feature_set(pair_fset, ft, subst)? {
-                    for (to, _) in parse_syntax_node(pair_lnode.clone(), ft, subst)? {
-                        lexicon.add_entry(SyntaxValue::Features(from.clone()), to);
-                    }
+    pair: &Pair<Rule>,
+    feature: &K,
+) -> Result<(), Diagnostic> {
+    let message = match ft.get_from_value(feature) {
+        Some(category) => format!("{feature} is already declared as a value of {category}"),
+        None if ft.is_declared(feature) => format!("{feature} is already declared as a category"),
+        None => return Ok(()),
+    };
+    Err(diagnostic(pair, Severity::Error, message))
+}
+
+fn parse_functional_entry<K: KeyType>(
+    pair: Pair<Rule>,
+    loader: &mut Loader<K>,
+) -> Result<(SyntaxValue<K>, LexiconNode<K>), Diagnostic> {
+    match pair.as_rule() {
+        Rule::functional_entry => {
+            let mut inner = pair.into_inner();
+            let pair_fset = inner.next().expect("Parsing functional entry: no from");
+            let pair_lnode = inner.next().expect("Parsing functional entry: no to");
+
+            let mut variables = VariableTable::new();
+            let from = parse_feature_set(pair_fset, loader, &mut variables)?;
+            let to = parse_syntax_node(pair_lnode, loader, &mut variables)?;
+            Ok((SyntaxValue::Features(from), to))
+        }
+        r => unreachable!("Parsing functional entries: unexpected rule: {r:?}"),
+    }
+}
+
+fn parse_morphology_entry<K: KeyType>(
+    pair: Pair<Rule>,
+    loader: &mut Loader<K>,
+) -> Result<SuffixRule<K>, Diagnostic> {
+    match pair.as_rule() {
+        Rule::morphology_entry => {
+            let mut inner = pair.into_inner();
+            let pair_suffix = inner.next().expect("Parsing suffix rule: no suffix");
+            let pair_fset = inner.next().expect("Parsing suffix rule: no features");
+
+            let features = parse_feature_set(pair_fset.clone(), loader, &mut VariableTable::new())?;
+            values_only(&pair_fset, &features)?;
+            Ok(SuffixRule {
+                suffix: pair_suffix.as_str().trim_start_matches('-').to_string(),
+                features,
+            })
+        }
+        r => unreachable!("Parsing suffix rules: unexpected rule: {r:?}"),
+    }
+}
+
+/// the lemma, for diagnostics, and the paradigm
+fn parse_paradigm_entry<'a, K: KeyType>(
+    pair: Pair<'a, Rule>,
+    loader: &mut Loader<K>,
+) -> Result<(Pair<'a, Rule>, Paradigm<K>), Diagnostic> {
+    match pair.as_rule() {
+        Rule::paradigm_entry => {
+            let mut inner = pair.into_inner();
+            let pair_lemma = inner.next().expect("Parsing paradigm: no lemma");
+            let lemma = parse_word(&pair_lemma)?;
+            let mut cells = Vec::new();
+            for pair_cell in inner {
+                let mut inner = pair_cell.into_inner();
+                let pair_fset = inner.next().expect("Parsing paradigm cell: no features");
+                let pair_form = inner.next().expect("Parsing paradigm cell: no form");
+
+                let mut features = FeatureSet::new();
+                let mut variables = VariableTable::new();
+                for pair_f in pair_fset.clone().into_inner() {
+                    parse_feature(&mut features, pair_f, loader, &mut variables)?;
                 }
+                values_only(&pair_fset, &features)?;
+                cells.push((features, parse_word(&pair_form)?));
             }
-            r => unreachable!("Parsing functional entries: unexpected rule: {r:?}"),
+            Ok((pair_lemma, Paradigm { lemma, cells }))
         }
+        r => unreachable!("Parsing paradigms: unexpected rule: {r:?}"),
+    }
+}
+
+/// inflection adds values, a category without one would be a variable bound to nothing
+fn values_only<K: KeyType>(pair: &Pair<Rule>, features: &FeatureSet<K>) -> Result<(), Diagnostic> {
+    let mut categories = features.categories();
+    match categories.find(|category| features.get_variable(category).is_some()) {
+        Some(category) => {
+            let message = format!("{category} is not a value, an inflection adds values only");
+            Err(diagnostic(pair, Severity::Error, message))
+        }
+        None => Ok(()),
     }
-    Ok(())
 }
 
 fn parse_syntax_node<K: KeyType>(
     pair: Pair<Rule>,
-    ft: &FeatureTopology<K>,
-    subst: SubstitutionTable<K>,
-) -> Result<ParserState<LexiconNode<K>, K>, String> {
+    loader: &mut Loader<K>,
+    variables: &mut VariableTable<K>,
+) -> Result<LexiconNode<K>, Diagnostic> {
     match pair.as_rule() {
-        Rule::lambda => parse_lambda(pair, ft, subst),
-        Rule::moved => parse_moved(pair, ft, subst),
-        Rule::feature_set => parse_feature_set(pair, ft, subst).map(|ps| {
-            ps.into_iter()
-                .map(|(fs, subst)| {
-                    (
-                        LexiconNode::Value {
-                            value: SyntaxValue::Features(fs),
-                        },
-                        subst,
-                    )
-                })
-                .collect()
+        Rule::lambda => parse_lambda(pair, loader, variables),
+        Rule::moved => parse_moved(pair, loader, variables),
+        Rule::feature_set => Ok(LexiconNode::Value {
+            value: SyntaxValue::Features(parse_feature_set(pair, loader, variables)?),
         }),
         r => unreachable!("Parsing syntax node: unexpected rule: {r:?}"),
     }
@@ -134,137 +380,309 @@ fn parse_syntax_node<K: KeyType>(
 
 fn parse_lambda<K: KeyType>(
     pair: Pair<Rule>,
-    ft: &FeatureTopology<K>,
-    subst: SubstitutionTable<K>,
-) -> Result<ParserState<LexiconNode<K>, K>, String> {
+    loader: &mut Loader<K>,
+    variables: &mut VariableTable<K>,
+) -> Result<LexiconNode<K>, Diagnostic> {
     let mut inner = pair.into_inner();
     let pair_l = inner.next().expect("Parsing lambda: no left");
     let pair_dir = inner.next().expect("Parsing lambda: no direction");
     let pair_r = inner.next().expect("Parsing lambda: no right");
 
-    let mut res = Vec::new();
-    let project = parse_project(pair_dir)?;
-    for (l_lnode, subst) in parse_syntax_node(pair_l, ft, subst)? {
-        for (r_lnode, subst) in parse_syntax_node(pair_r.clone(), ft, subst)? {
-            res.push((
-                LexiconNode::Lambda {
-                    from: Box::new(l_lnode.clone()),
-                    to: Box::new(r_lnode.clone()),
-                    project,
-                },
-                subst,
-            ));
-        }
-    }
-    Ok(res)
+    Ok(LexiconNode::Lambda {
+        from: Box::new(parse_syntax_node(pair_l, loader, variables)?),
+        project: parse_project(pair_dir),
+        to: Box::new(parse_syntax_node(pair_r, loader, variables)?),
+    })
 }
 
 fn parse_moved<K: KeyType>(
     pair: Pair<Rule>,
-    ft: &FeatureTopology<K>,
-    subst: SubstitutionTable<K>,
-) -> Result<ParserState<LexiconNode<K>, K>, String> {
+    loader: &mut Loader<K>,
+    variables: &mut VariableTable<K>,
+) -> Result<LexiconNode<K>, Diagnostic> {
     let pair_fset = pair
         .into_inner()
         .next()
         .expect("Parsing moved: no features");
-    let res = parse_feature_set(pair_fset, ft, subst)?;
-    Ok(res
-        .into_iter()
-        .map(|(fset, subst)| (LexiconNode::Moved { from: fset }, subst))
-        .collect())
+    let from = parse_feature_set(pair_fset, loader, variables)?;
+    Ok(LexiconNode::Moved { from })
 }
 
 fn parse_feature_set<K: KeyType>(
     pair: Pair<Rule>,
-    ft: &FeatureTopology<K>,
-    subst: SubstitutionTable<K>,
-) -> Result<ParserState<FeatureSet<K>, K>, String> {
-    let fset = FeatureSet::new();
-    let mut res = vec![(fset, subst)];
+    loader: &mut Loader<K>,
+    variables: &mut VariableTable<K>,
+) -> Result<FeatureSet<K>, Diagnostic> {
+    let mut fset = FeatureSet::new();
     for pair_f in pair.into_inner() {
-        let mut new_res = Vec::new();
-        while let Some((fset, subst)) = res.pop() {
-            let feature_parses = parse_feature(pair_f.clone(), ft, subst)?;
-            for ((feature_cat, feature_val), subst) in feature_parses {
-                let mut fset = fset.clone();
-                fset.insert(feature_cat, feature_val);
-                new_res.push((fset, subst));
+        match pair_f.as_rule() {
+            Rule::feature => parse_feature(&mut fset, pair_f, loader, variables)?,
+            Rule::absent_feature => {
+                let pair_cat = pair_f
+                    .into_inner()
+                    .next()
+                    .expect("Parsing absent: no category");
+                let category = parse_category(&pair_cat, &loader.ft)?;
+                fset.insert_constraint(category, Constraint::Absent);
             }
+            Rule::none_of_feature | Rule::one_of_feature => {
+                let rule = pair_f.as_rule();
+                let mut inner = pair_f.into_inner();
+                let pair_cat = inner.next().expect("Parsing constraint: no category");
+                let category = parse_category(&pair_cat, &loader.ft)?;
+                let pair_vals = inner.next().expect("Parsing constraint: no values");
+                let mut values = BTreeSet::new();
+                for pair_val in pair_vals.into_inner() {
+                    let value = parse_word(&pair_val)?;
+                    if loader.ft.get_from_value(&value).as_ref() != Some(&category) {
+                        let declared = loader.ft.values(&category).map(K::to_string);
+                        let suggestion = closest(&value.to_string(), declared);
+                        let message = format!(
+                            "{value} is not a value of {category}{}",
+                            did_you_mean(suggestion.as_deref())
+                        );
+                        return Err(diagnostic(&pair_val, Severity::Error, message));
+                    }
+                    values.extend(loader.ft.get_leaves(&value));
+                }
+                // the value is shared by the whole entry, like that of a bare category
+                fset.insert_variable(category.clone(), variable_of(variables, category.clone()));
+                let constraint = match rule {
+                    Rule::one_of_feature => Constraint::OneOf(values),
+                    _ => Constraint::NoneOf(values),
+                };
+                fset.insert_constraint(category, constraint);
+            }
+            r => unreachable!("Parsing feature set: unexpected rule: {r:?}"),
         }
-        res = new_res;
     }
-
-    Ok(res)
+    Ok(fset)
 }
 
 fn parse_feature<K: KeyType>(
+    fset: &mut FeatureSet<K>,
     pair: Pair<Rule>,
-    ft: &FeatureTopology<K>,
-    subst: SubstitutionTable<K>,
-) -> Result<ParserState<(K, Option<K>), K>, String> {
-    let k = parse_word(pair)?;
-    match subst.get(&k) {
-        Some(sub) => Ok(vec![(sub.to_owned(), subst)]),
-        None => {
-            if ft.is_category(&k) {
-                let vals = ft
-                    .get_from_category(&k)
-                    .expect("Parsing feature: category {k} has no values");
-                let mut res = Vec::new();
-                for val in vals {
-                    let mut subst = subst.clone();
-                    let sub = (k, Some(val.to_owned()));
-                    subst.insert(k, sub.to_owned());
-                    res.push((sub, subst));
-                }
-                Ok(res)
-            } else {
-                let sub = match ft.get_from_value(&k) {
-                    Some(cat) => (cat, Some(k)),
-                    None => (k, None),
-                };
-                let mut subst = subst.clone();
-                subst.insert(k, sub);
-                Ok(vec![(sub, subst)])
+    loader: &mut Loader<K>,
+    variables: &mut VariableTable<K>,
+) -> Result<(), Diagnostic> {
+    let k = parse_word(&pair)?;
+    let ft = &loader.ft;
+    if ft.is_category(&k) {
+        // a category without a value is a variable, shared by the whole entry
+        fset.insert_variable(k.clone(), variable_of(variables, k));
+    } else {
+        match ft.get_from_value(&k) {
+            // a supertype is any of its subtypes, e.g., `local` is `person=1st|2nd`
+            Some(cat) if ft.is_supertype(&k) => {
+                fset.insert_variable(cat.clone(), variable_of(variables, cat.clone()));
+                fset.insert_constraint(cat, Constraint::OneOf(ft.get_leaves(&k)));
+            }
+            Some(cat) => fset.insert(cat, Some(k)),
+            None => {
+                loader.check_declared(&pair, &k)?;
+                fset.insert(k, None)
             }
         }
     }
+    Ok(())
+}
+
+fn variable_of<K: KeyType>(variables: &mut VariableTable<K>, category: K) -> Variable {
+    let next = variables.len();
+    *variables.entry(category).or_insert(next)
+}
+
+fn parse_category<K: KeyType>(pair: &Pair<Rule>, ft: &FeatureTopology<K>) -> Result<K, Diagnostic> {
+    let category = parse_word(pair)?;
+    if ft.is_category(&category) {
+        return Ok(category);
+    }
+    let suggestion = closest(&category.to_string(), ft.categories().map(K::to_string));
+    let message = format!(
+        "{category} is not a feature category{}",
+        did_you_mean(suggestion.as_deref())
+    );
+    Err(diagnostic(pair, Severity::Error, message))
 }
 
-fn parse_project(pair: Pair<Rule>) -> Result<bool, String> {
+fn parse_project(pair: Pair<Rule>) -> bool {
     match pair.as_rule() {
-        Rule::right_projection => Ok(true),
-        Rule::left_projection => Ok(false),
+        Rule::right_projection => true,
+        Rule::left_projection => false,
         r => unreachable!("Parsing direction: unexpected rule: {r:?}"),
     }
 }
 
-fn parse_word<K: KeyType>(pair: Pair<Rule>) -> Result<K, String> {
+fn parse_word<K: KeyType>(pair: &Pair<Rule>) -> Result<K, Diagnostic> {
     let s = pair.as_str().trim();
-    K::from_str(s).map_err(|_| format!("FromStr: Cannot parse {s}"))
+    K::from_str(s)
+        .map_err(|_| diagnostic(pair, Severity::Error, format!("FromStr: Cannot parse {s}")))
 }
 
-fn parse_lexical_entries<K: KeyType>(
-    lexicon: &mut impl Lexicon<K>,
-    pairs: Vec<Pair<Rule>>,
-    topology: &FeatureTopology<K>,
-) -> Result<(), String> {
-    for pair in pairs {
-        match pair.as_rule() {
-            Rule::lexical_entry => {
-                let mut inner = pair.into_inner();
-                let pair_lexi = inner.next().expect("Parsing lexical entry: no from");
-                let pair_lnode = inner.next().expect("Parsing lexical entry: no to");
-
-                let from = SyntaxValue::Item(parse_word(pair_lexi)?);
-                let subst = SubstitutionTable::new();
-                for (to, _) in parse_syntax_node(pair_lnode, topology, subst)? {
-                    lexicon.add_entry(from.clone(), to);
-                }
-            }
-            r => unreachable!("Parsing lexical entries: unexpected rule: {r:?}"),
+fn parse_lexical_entry<K: KeyType>(
+    pair: Pair<Rule>,
+    loader: &mut Loader<K>,
+) -> Result<(SyntaxValue<K>, LexiconNode<K>), Diagnostic> {
+    match pair.as_rule() {
+        Rule::lexical_entry => {
+            let mut inner = pair.into_inner();
+            let pair_lexi = inner.next().expect("Parsing lexical entry: no from");
+            let pair_lnode = inner.next().expect("Parsing lexical entry: no to");
+
+            let from = SyntaxValue::Item(parse_word(&pair_lexi)?);
+            let mut variables = VariableTable::new();
+            let to = parse_syntax_node(pair_lnode, loader, &mut variables)?;
+            Ok((from, to))
         }
+        r => unreachable!("Parsing lexical entries: unexpected rule: {r:?}"),
+    }
+}
+
+fn did_you_mean(suggestion: Option<&str>) -> String {
+    match suggestion {
+        Some(suggestion) => format!(", did you mean {suggestion}?"),
+        None => String::new(),
+    }
+}
+
+fn diagnostic(pair: &Pair<Rule>, severity: Severity, message: String) -> Diagnostic {
+    let (line, column) = pair.as_span().start_pos().line_col();
+    Diagnostic {
+        severity,
+        line,
+        column,
+        message,
+    }
+}
+
+pub fn syntax_error(error: pest::error::Error<Rule>) -> Diagnostic {
+    let (line, column) = match error.line_col {
+        LineColLocation::Pos(pos) | LineColLocation::Span(pos, _) => pos,
+    };
+    Diagnostic {
+        severity: Severity::Error,
+        line,
+        column,
+        message: error.variant.message().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interner::GlobalKey;
+    use crate::lexicon::SimpleLexicon;
+    use std::str::FromStr;
+
+    fn features(features: &[(&str, Option<&str>)]) -> SyntaxValue<GlobalKey> {
+        let key = |s| GlobalKey::from_str(s).unwrap();
+        let mut fs = FeatureSet::new();
+        for (category, value) in features {
+            fs.insert(key(category), value.map(key));
+        }
+        SyntaxValue::Features(fs)
+    }
+
+    #[test]
+    fn parse_constraints_and_hierarchy() {
+        let input = "[Features]
+case = subj, obj, nocase
+person = 1st, 2nd, 3rd
+[Functional]
+DetP-case!=obj = (A > B)
+DetP-person=1st|2nd = (C > D)
+DetP-!case = E
+";
+        let mut lexicon: SimpleLexicon<GlobalKey> = SimpleLexicon::new();
+        PestLexiconParser::parse_str(&mut lexicon, input).unwrap();
+
+        let subj = features(&[("DetP", None), ("case", Some("subj"))]);
+        let entries = lexicon.get_entries(&subj);
+        assert_eq!(entries.len(), 1);
+        let entry = entries.into_iter().next().unwrap();
+        assert_eq!(format!("{entry}"), "interpretation = (A > B)");
+
+        let obj = features(&[
+            ("DetP", None),
+            ("case", Some("obj")),
+            ("person", Some("2nd")),
+        ]);
+        let entries = lexicon.get_entries(&obj);
+        assert_eq!(entries.len(), 1);
+        let entry = entries.into_iter().next().unwrap();
+        assert_eq!(format!("{entry}"), "interpretation = (C > D)");
+
+        let caseless = features(&[("DetP", None), ("person", Some("3rd"))]);
+        let entries = lexicon.get_entries(&caseless);
+        assert_eq!(entries.len(), 1);
+        let entry = entries.into_iter().next().unwrap();
+        assert_eq!(format!("{entry}"), "interpretation = E");
+
+        let hierarchy = "[Features]
+person = local{1st, 2nd}, 3rd
+[Functional]
+DetP-local = (A > B)
+DetP-person!=local = C
+";
+        let mut lexicon: SimpleLexicon<GlobalKey> = SimpleLexicon::new();
+        PestLexiconParser::parse_str(&mut lexicon, hierarchy).unwrap();
+        let second = features(&[("DetP", None), ("person", Some("2nd"))]);
+        let entries = lexicon.get_entries(&second);
+        assert_eq!(entries.len(), 1);
+        let entry = entries.into_iter().next().unwrap();
+        assert_eq!(format!("{entry}"), "interpretation = (A > B)");
+        let third = features(&[("DetP", None), ("person", Some("3rd"))]);
+        let entries = lexicon.get_entries(&third);
+        let entry = entries.into_iter().next().unwrap();
+        assert_eq!(format!("{entry}"), "interpretation = C");
+
+        let unknown = "[Features]\ncase = subj\n[Functional]\nDetP-case!=obj = E\n";
+        let errors = PestLexiconParser::parse_str(&mut lexicon, unknown).unwrap_err();
+        assert_eq!(errors[0].message, "obj is not a value of case");
+    }
+
+    #[test]
+    fn validate_topology() {
+        let input = "[Features]
+number = sg, pl, sg
+case = subj, number
+DetP
+[Functional]
+DetP-nubmer = (DetP-sgg > NounP)
+DetP-cas!=obj = E
+";
+        let mut lexicon: SimpleLexicon<GlobalKey> = SimpleLexicon::new();
+        let diagnostics = PestLexiconParser::parse_str(&mut lexicon, input).unwrap_err();
+        let diagnostics = diagnostics
+            .iter()
+            .map(|d| format!("{d}"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics,
+            vec![
+                "2:18: error: sg is already declared as a value of number",
+                "3:14: error: number is already declared as a category",
+                "6:6: warning: nubmer is not declared, did you mean number?",
+                "6:21: warning: sgg is not declared, did you mean sg?",
+                "7:6: error: cas is not a feature category, did you mean case?",
+            ]
+        );
+
+        // every feature is declared in strict mode, syntactic categories included
+        let strict = "[Features]\nnumber = sg, pl\nDetP\n[Functional]\nDetP-sg = NounP\n";
+        let mut lexicon: SimpleLexicon<GlobalKey> = SimpleLexicon::new();
+        let errors = PestLexiconParser::parse_str_strict(&mut lexicon, strict).unwrap_err();
+        assert_eq!(
+            format!("{}", errors[0]),
+            "5:11: error: NounP is not declared"
+        );
+        // nothing of a lexicon with errors is loaded
+        assert!(lexicon.entries().is_empty() && lexicon.declarations().is_empty());
+        let warnings = PestLexiconParser::parse_str(&mut lexicon, strict).unwrap();
+        assert!(warnings.is_empty());
+
+        let shipped = include_str!("../../../../assets/lexicons/en.lexicon");
+        let mut lexicon: SimpleLexicon<GlobalKey> = SimpleLexicon::new();
+        PestLexiconParser::parse_str_strict(&mut lexicon, shipped).unwrap();
     }
-    Ok(())
 }
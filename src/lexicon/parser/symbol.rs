@@ -0,0 +1,39 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Section,
+    Category,
+    Value,
+    Suffix,
+    Functional,
+    Lexical,
+    Paradigm,
+    Guess,
+}
+
+/// a named span of a lexicon, with the spans within it, e.g., a section and its entries;
+/// lines and columns are from 1, and `end` is past the last character
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+    pub children: Vec<Symbol>,
+}
+
+impl Symbol {
+    /// this symbol or the first within it named `name`
+    pub fn find(&self, name: &str) -> Option<&Symbol> {
+        if self.name == name {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(name))
+    }
+
+    /// this symbol and every symbol within it
+    pub fn flatten(&self) -> Vec<&Symbol> {
+        let mut symbols = vec![self];
+        symbols.extend(self.children.iter().flat_map(Symbol::flatten));
+        symbols
+    }
+}
@@ -1,5 +1,8 @@
+use super::ParseResult;
 use crate::lexicon::Lexicon;
 
 pub trait LexiconParser<K> {
-    fn parse_str(lexicon: &mut impl Lexicon<K>, input: &str) -> Result<(), String>;
+    fn parse_str(lexicon: &mut impl Lexicon<K>, input: &str) -> ParseResult;
+    /// like `parse_str`, but every feature, including bare categories, must be declared
+    fn parse_str_strict(lexicon: &mut impl Lexicon<K>, input: &str) -> ParseResult;
 }
@@ -0,0 +1,242 @@
+use super::parser::PestLexiconParser;
+use super::{Declaration, Lexicon, LexiconNode};
+use crate::syntax::{Constraint, FeatureSet, SyntaxValue};
+use std::fmt::Display;
+
+/// `lexicon` written back as a `.lexicon` file that loads as the same lexicon; features
+/// used by entries but never declared are declared as they are found, and a supertype is
+/// written as the values it stands for
+pub fn serialize<K>(lexicon: &impl Lexicon<K>) -> String
+where
+    K: Display + Clone + Ord,
+{
+    let mut declarations = lexicon.declarations();
+    let mut functional = Vec::new();
+    let mut lexical = Vec::new();
+    for (from, node) in lexicon.entries() {
+        for fs in node.features().into_iter().chain(match &from {
+            SyntaxValue::Features(key) => Some(key),
+            SyntaxValue::Item(_) => None,
+        }) {
+            declare_used(&mut declarations, fs);
+        }
+        match from {
+            SyntaxValue::Features(key) => {
+                functional.push(format!("{} = {}", features(&key), node_source(&node)))
+            }
+            SyntaxValue::Item(item) => lexical.push(format!("{} = {}", item, node_source(&node))),
+        }
+    }
+    functional.sort();
+    lexical.sort();
+    let rules = lexicon.rules();
+    for rule in &rules {
+        declare_used(&mut declarations, &rule.features);
+    }
+    let unknown = lexicon.unknown();
+    for fs in unknown.iter().flat_map(LexiconNode::features) {
+        declare_used(&mut declarations, fs);
+    }
+    let paradigms = lexicon.paradigms();
+    for (cell, _) in paradigms.iter().flat_map(|paradigm| &paradigm.cells) {
+        declare_used(&mut declarations, cell);
+    }
+
+    let mut source = String::from("[Features]\n");
+    let mut categories = Vec::new();
+    for declaration in &declarations {
+        if !categories.contains(&&declaration.category) {
+            categories.push(&declaration.category);
+        }
+    }
+    for category in categories {
+        match values(&declarations, category, None) {
+            Some(values) => source.push_str(&format!("{category} = {values}\n")),
+            None => source.push_str(&format!("{category}\n")),
+        }
+    }
+    if !rules.is_empty() {
+        source.push_str("\n[Morphology]\n");
+    }
+    rules
+        .into_iter()
+        .for_each(|rule| source.push_str(&format!("{rule}\n")));
+    source.push_str("\n[Functional]\n");
+    functional
+        .into_iter()
+        .for_each(|entry| source.push_str(&format!("{entry}\n")));
+    source.push_str("\n[Lexical]\n");
+    lexical
+        .into_iter()
+        .for_each(|entry| source.push_str(&format!("{entry}\n")));
+    if !paradigms.is_empty() {
+        source.push_str("\n[Paradigms]\n");
+    }
+    paradigms
+        .into_iter()
+        .for_each(|paradigm| source.push_str(&format!("{paradigm}\n")));
+    if !unknown.is_empty() {
+        source.push_str("\n[Unknown]\n");
+    }
+    unknown
+        .into_iter()
+        .for_each(|node| source.push_str(&format!("{}\n", node_source(&node))));
+
+    // aligned like any other lexicon, the source is valid by construction
+    PestLexiconParser::format(&source).unwrap_or(source)
+}
+
+/// declares the values and constrained values of `fs` that are not declared yet
+fn declare_used<K: Clone + Ord>(declarations: &mut Vec<Declaration<K>>, fs: &FeatureSet<K>) {
+    let mut used = fs
+        .iter()
+        .filter_map(|(category, value)| Some((category.clone(), value.clone()?)))
+        .collect::<Vec<_>>();
+    for category in fs.categories() {
+        if let Some(Constraint::OneOf(values) | Constraint::NoneOf(values)) =
+            fs.get_constraint(category)
+        {
+            used.extend(values.iter().map(|value| (category.clone(), value.clone())));
+        }
+    }
+    for (category, value) in used {
+        let declared = declarations
+            .iter()
+            .any(|d| d.value.as_ref() == Some(&value));
+        if !declared {
+            declarations.push(Declaration {
+                category,
+                value: Some(value),
+                supertype: None,
+            });
+        }
+    }
+}
+
+/// `local{1st, 2nd}, 3rd`, the values of `category` under `supertype`, if there are any
+fn values<K: Display + PartialEq>(
+    declarations: &[Declaration<K>],
+    category: &K,
+    supertype: Option<&K>,
+) -> Option<String> {
+    let values = declarations
+        .iter()
+        .filter(|d| &d.category == category && d.supertype.as_ref() == supertype)
+        .filter_map(|d| d.value.as_ref())
+        .map(|value| match values(declarations, category, Some(value)) {
+            Some(subtypes) => format!("{value}{{{subtypes}}}"),
+            None => format!("{value}"),
+        })
+        .collect::<Vec<_>>();
+    match values.is_empty() {
+        true => None,
+        false => Some(values.join(", ")),
+    }
+}
+
+/// `DetP-subj-number-person=1st|2nd-!case`, categories first, then values, then variables
+/// and constraints; each category has a single variable, as in an entry that is parsed
+pub fn features<K: Display + Clone + Ord>(fs: &FeatureSet<K>) -> String {
+    let mut categories = Vec::new();
+    let mut values = Vec::new();
+    for (category, value) in fs.iter() {
+        match value {
+            Some(value) => values.push(format!("{value}")),
+            None => categories.push(format!("{category}")),
+        }
+    }
+    let mut variables = Vec::new();
+    for category in fs
+        .categories()
+        .filter(|category| fs.get_variable(category).is_some())
+    {
+        let join = |values: &std::collections::BTreeSet<K>| {
+            let values = values.iter().map(|value| format!("{value}"));
+            values.collect::<Vec<_>>().join("|")
+        };
+        variables.push(match fs.get_constraint(category) {
+            Some(Constraint::OneOf(values)) => format!("{category}={}", join(values)),
+            Some(Constraint::NoneOf(values)) => format!("{category}!={}", join(values)),
+            _ => format!("{category}"),
+        });
+    }
+    variables.extend(fs.absent().map(|category| format!("!{category}")));
+    categories.sort();
+    values.sort();
+    variables.sort();
+    categories.extend(values);
+    categories.extend(variables);
+    categories.join("-")
+}
+
+pub fn node_source<K: Display + Clone + Ord>(node: &LexiconNode<K>) -> String {
+    match node {
+        LexiconNode::Value {
+            value: SyntaxValue::Features(fs),
+        } => features(fs),
+        LexiconNode::Value {
+            value: SyntaxValue::Item(item),
+        } => format!("{item}"),
+        LexiconNode::Lambda { from, to, project } => {
+            let project = if *project { ">>" } else { ">" };
+            format!("({} {} {})", node_source(from), project, node_source(to))
+        }
+        LexiconNode::Moved { from } => format!("MOVED({})", features(from)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interner::GlobalKey;
+    use crate::lexicon::SimpleLexicon;
+    use crate::lexicon::parser::LexiconParser;
+
+    #[test]
+    fn serialize_round_trip() {
+        let input = "[Features]
+person = local{1st, 2nd}, 3rd
+case = subj, obj
+DetP
+NounP
+TenseP
+[Morphology]
+-s = NounP-3rd
+[Functional]
+DetP-local = ((MOVED(DetP-person) > NounP-case!=obj) >> TenseP-!case)
+[Lexical]
+me = DetP-1st-subj
+[Paradigms]
+me: obj=me
+";
+        let mut lexicon: SimpleLexicon<GlobalKey> = SimpleLexicon::new();
+        PestLexiconParser::parse_str_strict(&mut lexicon, input).unwrap();
+        let serialized = serialize(&lexicon);
+        assert_eq!(
+            serialized,
+            "[Features]
+person = local{1st, 2nd}, 3rd
+case   = subj, obj
+DetP
+NounP
+TenseP
+
+[Morphology]
+-s = NounP-3rd
+
+[Functional]
+DetP-person=1st|2nd = ((MOVED(DetP-person) > NounP-case!=obj) >> TenseP-!case)
+
+[Lexical]
+me = DetP-1st-subj
+
+[Paradigms]
+me: obj=me
+"
+        );
+
+        let mut reloaded: SimpleLexicon<GlobalKey> = SimpleLexicon::new();
+        PestLexiconParser::parse_str_strict(&mut reloaded, &serialized).unwrap();
+        assert_eq!(serialize(&reloaded), serialized);
+    }
+}
@@ -1,3 +1,3 @@
 mod r#struct;
 
-pub use r#struct::SimpleLexicon;
+pub use r#struct::{KeyedNode, SimpleLexicon};
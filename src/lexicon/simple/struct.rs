@@ -1,39 +1,107 @@
-use super::super::{Lexicon, LexiconEntry, LexiconNode};
-use crate::syntax::{FeatureSet, SyntaxValue};
+use super::super::{
+    Declaration, Lexicon, LexiconEntry, LexiconNode, Paradigm, SuffixRule, inflect,
+};
+use crate::syntax::{Bindings, FeatureSet, SyntaxValue};
 use crate::trie::TrieMultiMap;
 use std::cmp::Ord;
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
 
+/// a functional entry with the features it is looked up by
+pub type KeyedNode<K> = (FeatureSet<K>, LexiconNode<K>);
+
 #[derive(Debug)]
 pub struct SimpleLexicon<K: Debug + Clone + Ord + Hash> {
     lexical: HashMap<K, HashSet<LexiconNode<K>>>,
-    functional: TrieMultiMap<(K, Option<K>), LexiconNode<K>>,
+    /// keyed by the values of the key only, as its variables match any value
+    functional: TrieMultiMap<(K, Option<K>), KeyedNode<K>>,
+    /// the values each category takes in the keys
+    key_values: HashMap<K, HashSet<Option<K>>>,
+    declarations: Vec<Declaration<K>>,
+    rules: Vec<SuffixRule<K>>,
+    /// the lexical items by how they are spelled, for the stems of inflected words
+    spellings: HashMap<String, K>,
+    paradigms: Vec<Paradigm<K>>,
+    /// the lemma and the values of each form in a paradigm
+    forms: HashMap<K, Vec<(K, FeatureSet<K>)>>,
+    unknown: Vec<LexiconNode<K>>,
 }
 
-impl<K: Debug + Clone + Ord + Hash> SimpleLexicon<K> {
+impl<K: Debug + Display + Clone + Ord + Hash> SimpleLexicon<K> {
     pub fn new() -> Self {
         SimpleLexicon {
             lexical: HashMap::new(),
             functional: TrieMultiMap::new(),
+            key_values: HashMap::new(),
+            declarations: Vec::new(),
+            rules: Vec::new(),
+            spellings: HashMap::new(),
+            paradigms: Vec::new(),
+            forms: HashMap::new(),
+            unknown: Vec::new(),
         }
     }
 
+    /// every lexical item with a node it stands for
+    pub fn lexical_entries(&self) -> impl Iterator<Item = (&K, &LexiconNode<K>)> {
+        let entries = self.lexical.iter();
+        entries.flat_map(|(item, nodes)| nodes.iter().map(move |node| (item, node)))
+    }
+
+    /// every functional entry with the features it is looked up by
+    pub fn functional_entries(&self) -> Vec<KeyedNode<K>> {
+        self.functional.iter().map(|(_, entry)| entry).collect()
+    }
+
     fn get_lexical_entries(&self, from: &K) -> HashSet<LexiconEntry<K>> {
-        self.lexical
-            .get(from)
-            .cloned()
-            .unwrap_or_default()
-            .into_iter()
-            .map(|node| LexiconEntry::Lexical(node.clone()))
-            .collect()
+        let nodes = self.lexical.get(from).into_iter().flatten();
+        let mut entries = nodes
+            .map(|node| LexiconEntry::Lexical(node.clone(), None))
+            .collect::<HashSet<_>>();
+        for (lemma, cell) in self.forms.get(from).into_iter().flatten() {
+            let nodes = self.lexical.get(lemma).into_iter().flatten();
+            let inflected = nodes.filter_map(|node| inflect(cell, node));
+            entries.extend(inflected.map(|node| LexiconEntry::Lexical(node, Some(lemma.clone()))));
+        }
+        if !self.rules.is_empty() {
+            entries.extend(self.inflections(&from.to_string()));
+        }
+        entries
+    }
+
+    /// the entries of `word` as an inflection of a stem in the lexicon
+    fn inflections(&self, word: &str) -> Vec<LexiconEntry<K>> {
+        let mut entries = Vec::new();
+        for rule in &self.rules {
+            let Some(stem) = rule.stem(word, &self.spellings) else {
+                continue;
+            };
+            let nodes = self.lexical.get(stem).into_iter().flatten();
+            let inflected = nodes.filter_map(|node| rule.inflect(node));
+            entries.extend(inflected.map(|node| LexiconEntry::Lexical(node, Some(stem.clone()))));
+        }
+        entries
     }
 
     fn get_functional_entries(&self, from: &FeatureSet<K>) -> HashSet<LexiconEntry<K>> {
+        // a variable in hand may be bound to any value a key has for its category
+        let mut query = from
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect::<Vec<_>>();
+        for (category, values) in self.key_values.iter() {
+            if from.get_variable(category).is_some() {
+                query.extend(values.iter().map(|value| (category.clone(), value.clone())));
+            }
+        }
+        query.sort();
+
         self.functional
-            .get_subsets(from)
+            .get_subsets(&query)
             .into_iter()
+            .map(|(_, entry)| entry)
+            .filter(|(key, _)| key.unify(from, &mut Bindings::new()))
             .map(|(key, value)| {
                 let project = match value {
                     LexiconNode::Lambda {
@@ -43,12 +111,12 @@ impl<K: Debug + Clone + Ord + Hash> SimpleLexicon<K> {
                     } => !project,
                     _ => true,
                 };
-                let project = if project {
-                    Some(key.into_iter().collect())
-                } else {
-                    None
-                };
-                LexiconEntry::Functional { to: value, project }
+                let project = if project { Some(key.clone()) } else { None };
+                LexiconEntry::Functional {
+                    to: value,
+                    project,
+                    key,
+                }
             })
             .collect()
     }
@@ -56,12 +124,21 @@ impl<K: Debug + Clone + Ord + Hash> SimpleLexicon<K> {
 
 impl<K> Lexicon<K> for SimpleLexicon<K>
 where
-    K: Debug + Clone + Ord + Hash,
+    K: Debug + Display + Clone + Ord + Hash,
 {
     fn add_entry(&mut self, from: SyntaxValue<K>, to: LexiconNode<K>) -> bool {
         match from {
-            SyntaxValue::Item(key) => self.lexical.entry(key).or_default().insert(to),
-            SyntaxValue::Features(fs) => self.functional.insert(fs, to),
+            SyntaxValue::Item(key) => {
+                self.spellings.insert(key.to_string(), key.clone());
+                self.lexical.entry(key).or_default().insert(to)
+            }
+            SyntaxValue::Features(fs) => {
+                for (category, value) in fs.iter() {
+                    let values = self.key_values.entry(category.clone()).or_default();
+                    values.insert(value.clone());
+                }
+                self.functional.insert(fs.clone(), (fs, to))
+            }
         }
     }
     fn get_entries(&self, from: &SyntaxValue<K>) -> HashSet<LexiconEntry<K>> {
@@ -70,6 +147,43 @@ where
             SyntaxValue::Features(fs) => self.get_functional_entries(fs),
         }
     }
+    fn add_declaration(&mut self, declaration: Declaration<K>) {
+        self.declarations.push(declaration);
+    }
+    fn declarations(&self) -> Vec<Declaration<K>> {
+        self.declarations.clone()
+    }
+    fn add_rule(&mut self, rule: SuffixRule<K>) {
+        self.rules.push(rule);
+    }
+    fn rules(&self) -> Vec<SuffixRule<K>> {
+        self.rules.clone()
+    }
+    fn add_paradigm(&mut self, paradigm: Paradigm<K>) {
+        for (cell, form) in &paradigm.cells {
+            let forms = self.forms.entry(form.clone()).or_default();
+            forms.push((paradigm.lemma.clone(), cell.clone()));
+        }
+        self.paradigms.push(paradigm);
+    }
+    fn paradigms(&self) -> Vec<Paradigm<K>> {
+        self.paradigms.clone()
+    }
+    fn add_unknown(&mut self, node: LexiconNode<K>) {
+        self.unknown.push(node);
+    }
+    fn unknown(&self) -> Vec<LexiconNode<K>> {
+        self.unknown.clone()
+    }
+    fn entries(&self) -> Vec<(SyntaxValue<K>, LexiconNode<K>)> {
+        let lexical = self.lexical_entries();
+        let mut entries = lexical
+            .map(|(item, node)| (SyntaxValue::Item(item.clone()), node.clone()))
+            .collect::<Vec<_>>();
+        let functional = self.functional_entries().into_iter();
+        entries.extend(functional.map(|(key, node)| (SyntaxValue::Features(key), node)));
+        entries
+    }
 }
 
 impl<K> Display for SimpleLexicon<K>
@@ -84,8 +198,7 @@ where
             }
         }
         writeln!(f, "Functional:")?;
-        for (func_vec, interp) in self.functional.iter() {
-            let func: FeatureSet<K> = func_vec.into_iter().collect();
+        for (_, (func, interp)) in self.functional.iter() {
             writeln!(f, "  {func} = {interp}")?;
         }
         Ok(())
@@ -94,7 +207,10 @@ where
 
 #[cfg(test)]
 mod tests {
+    use crate::interner::GlobalKey;
+    use crate::lexicon::parser::{LexiconParser, PestLexiconParser};
     use crate::syntax::FeatureSet;
+    use std::str::FromStr;
 
     use super::*;
 
@@ -125,8 +241,8 @@ mod tests {
         assert_eq!(
             lexicon.get_entries(&SyntaxValue::Item("a")),
             [
-                LexiconEntry::Lexical(ab.clone()),
-                LexiconEntry::Lexical(abc.clone())
+                LexiconEntry::Lexical(ab.clone(), None),
+                LexiconEntry::Lexical(abc.clone(), None)
             ]
             .into_iter()
             .collect()
@@ -134,15 +250,73 @@ mod tests {
         assert_eq!(
             lexicon.get_entries(&SyntaxValue::Item("b")),
             [
-                LexiconEntry::Lexical(ab.clone()),
-                LexiconEntry::Lexical(abc.clone())
+                LexiconEntry::Lexical(ab.clone(), None),
+                LexiconEntry::Lexical(abc.clone(), None)
             ]
             .into_iter()
             .collect()
         );
         assert_eq!(
             lexicon.get_entries(&SyntaxValue::Item("c")),
-            [LexiconEntry::Lexical(abc.clone())].into_iter().collect()
+            [LexiconEntry::Lexical(abc.clone(), None)]
+                .into_iter()
+                .collect()
         );
     }
+
+    #[test]
+    fn lookup_with_variables() {
+        let mut lexicon: SimpleLexicon<&str> = SimpleLexicon::new();
+        let features = |number: Option<&'static str>| {
+            let mut fs = FeatureSet::from_category("DetH");
+            match number {
+                Some(number) => fs.insert("number", Some(number)),
+                None => fs.insert_variable("number", 0),
+            }
+            fs
+        };
+        let node = LexiconNode::Value {
+            value: SyntaxValue::Features(FeatureSet::from_category("DetP")),
+        };
+        lexicon.add_entry(SyntaxValue::Features(features(None)), node.clone());
+        lexicon.add_entry(SyntaxValue::Features(features(Some("pl"))), node.clone());
+
+        // a key variable matches any value in hand, and a variable in hand any key value
+        let sg = SyntaxValue::Features(features(Some("sg")));
+        assert_eq!(lexicon.get_entries(&sg).len(), 1);
+        let unknown = SyntaxValue::Features(features(None));
+        assert_eq!(lexicon.get_entries(&unknown).len(), 2);
+        let bare = SyntaxValue::Features(FeatureSet::from_category("DetH"));
+        assert_eq!(lexicon.get_entries(&bare).len(), 0);
+    }
+
+    #[test]
+    fn lookup_inflections() {
+        let input = "[Features]
+tense = present, past
+[Morphology]
+-ed = VerbH-past
+[Lexical]
+jump = VerbH
+eat = VerbH
+ir = VerbH
+[Paradigms]
+eat: past=ate
+ir: past=fué
+";
+        let mut lexicon: SimpleLexicon<GlobalKey> = SimpleLexicon::new();
+        PestLexiconParser::parse_str(&mut lexicon, input).unwrap();
+        let entries = |word: &str| {
+            let item = SyntaxValue::Item(GlobalKey::from_str(word).unwrap());
+            let entries = lexicon.get_entries(&item).into_iter();
+            let mut entries = entries.map(|entry| entry.to_string()).collect::<Vec<_>>();
+            entries.sort();
+            entries
+        };
+        assert_eq!(entries("jumped"), vec!["token = VerbH--tense:past (jump)"]);
+        assert_eq!(entries("ate"), vec!["token = VerbH--tense:past (eat)"]);
+        assert_eq!(entries("fué"), vec!["token = VerbH--tense:past (ir)"]);
+        assert_eq!(entries("jump"), vec!["token = VerbH"]);
+        assert!(entries("walked").is_empty());
+    }
 }
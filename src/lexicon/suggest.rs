@@ -0,0 +1,54 @@
+/// edit distance in which swapping two adjacent characters is a single edit
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = (a[i - 1] != b[j - 1]) as usize;
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + substitution);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+/// the candidate closest to `word`, if it is close enough to be a typo of it
+pub fn closest<S: AsRef<str>>(word: &str, candidates: impl IntoIterator<Item = S>) -> Option<S> {
+    let threshold = (word.chars().count() / 4).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(word, candidate.as_ref()), candidate))
+        .filter(|(distance, _)| (1..=threshold).contains(distance))
+        .min_by(|(a, a_str), (b, b_str)| (a, a_str.as_ref()).cmp(&(b, b_str.as_ref())))
+        .map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggest_close_words() {
+        assert_eq!(edit_distance("singular", "singualr"), 1);
+        assert_eq!(edit_distance("past", "pst"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(
+            closest("singualr", ["plural", "singular"]),
+            Some("singular")
+        );
+        assert_eq!(closest("TenseP", ["tense"]), None);
+        assert_eq!(closest("sg", ["sg", "pl"]), None);
+    }
+}
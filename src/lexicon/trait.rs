@@ -1,8 +1,26 @@
-use super::{LexiconEntry, LexiconNode};
+use super::{Declaration, LexiconEntry, LexiconNode, Paradigm, SuffixRule};
 use crate::syntax::SyntaxValue;
 use std::collections::HashSet;
 
 pub trait Lexicon<K> {
     fn add_entry(&mut self, from: SyntaxValue<K>, to: LexiconNode<K>) -> bool;
     fn get_entries(&self, from: &SyntaxValue<K>) -> HashSet<LexiconEntry<K>>;
+    /// keeps what `[Features]` declares, so that the lexicon can be written back
+    fn add_declaration(&mut self, declaration: Declaration<K>);
+    /// every declaration, in the order they were added
+    fn declarations(&self) -> Vec<Declaration<K>>;
+    /// a rule by which words not in the lexicon are looked up by their stem
+    fn add_rule(&mut self, rule: SuffixRule<K>);
+    /// every rule, in the order they were added
+    fn rules(&self) -> Vec<SuffixRule<K>>;
+    /// the irregular forms of a lemma, each looked up as the lemma with the values of its cell
+    fn add_paradigm(&mut self, paradigm: Paradigm<K>);
+    /// every paradigm, in the order they were added
+    fn paradigms(&self) -> Vec<Paradigm<K>>;
+    /// a node a token not in the lexicon may be guessed to be
+    fn add_unknown(&mut self, node: LexiconNode<K>);
+    /// every node from `[Unknown]`, in the order they were added
+    fn unknown(&self) -> Vec<LexiconNode<K>>;
+    /// every entry, lexical and functional, in no particular order
+    fn entries(&self) -> Vec<(SyntaxValue<K>, LexiconNode<K>)>;
 }
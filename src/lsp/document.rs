@@ -0,0 +1,214 @@
+use crate::interner::{GlobalKey, Interner};
+use crate::lexicon::parser::{LexiconParser, PestLexiconParser, Severity, Symbol, SymbolKind};
+use crate::lexicon::{SimpleLexicon, load_entry};
+use serde_json::{Value, json};
+
+/// an open `.lexicon` file, with its outline as of its last version without syntax errors
+#[derive(Debug)]
+pub struct Document {
+    text: String,
+    sections: Vec<Symbol>,
+}
+
+impl Document {
+    pub fn new(text: String) -> Self {
+        let sections = PestLexiconParser::outline(&text).unwrap_or_default();
+        Self { text, sections }
+    }
+
+    /// keeps the outline of the previous version if `text` does not parse, so that
+    /// symbols and definitions stay available while typing
+    pub fn update(&mut self, text: String) {
+        if let Ok(sections) = PestLexiconParser::outline(&text) {
+            self.sections = sections;
+        }
+        self.text = text;
+    }
+
+    /// the errors and warnings of loading the lexicon
+    pub fn diagnostics(&self) -> Value {
+        // each version is loaded with symbols of its own, dropped once it is checked
+        let _scope = Interner::new().enter();
+        let mut lexicon: SimpleLexicon<GlobalKey> = SimpleLexicon::new();
+        let diagnostics = match PestLexiconParser::parse_str(&mut lexicon, &self.text) {
+            Ok(diagnostics) | Err(diagnostics) => diagnostics,
+        };
+        let diagnostics = diagnostics.into_iter().map(|diagnostic| {
+            let severity = match diagnostic.severity {
+                Severity::Error => 1,
+                Severity::Warning => 2,
+            };
+            json!({
+                "range": self.word_range(diagnostic.line, diagnostic.column),
+                "severity": severity,
+                "source": "langbda",
+                "message": diagnostic.message,
+            })
+        });
+        Value::Array(diagnostics.collect())
+    }
+
+    /// the entries the entry at `line` is loaded as, with the features they are keyed by
+    pub fn hover(&self, line: usize) -> Option<Value> {
+        let entry = self.symbols().into_iter().find(|symbol| {
+            matches!(symbol.kind, SymbolKind::Functional | SymbolKind::Lexical)
+                && (symbol.start.0..=symbol.end.0).contains(&(line + 1))
+        })?;
+        let _scope = Interner::new().enter();
+        let lexicon: SimpleLexicon<GlobalKey> =
+            load_entry(&self.text, &self.sections, entry).ok()?;
+        let mut entries = lexicon
+            .functional_entries()
+            .iter()
+            .map(|(key, node)| format!("{key} = {node}"))
+            .collect::<Vec<_>>();
+        let lexical = lexicon.lexical_entries();
+        entries.extend(lexical.map(|(item, node)| format!("{item} = {node}")));
+
+        Some(json!({
+            "contents": {
+                "kind": "markdown",
+                "value": format!("```\n{}\n```", entries.join("\n")),
+            },
+            "range": range(entry.start, entry.end),
+        }))
+    }
+
+    /// where the feature at `line` and `character` is declared in `[Features]`
+    pub fn definition(&self, line: usize, character: usize) -> Option<Value> {
+        let word = self.word_at(line, character)?;
+        let sections = self
+            .sections
+            .iter()
+            .filter(|s| s.kind == SymbolKind::Section);
+        let features = sections.filter(|section| section.name == "[Features]");
+        let declaration = features
+            .into_iter()
+            .find_map(|section| section.find(&word))?;
+        Some(range(declaration.start, declaration.end))
+    }
+
+    /// every declared category and value
+    pub fn completion(&self) -> Value {
+        let items = self.features().into_iter().map(|symbol| {
+            let (kind, detail) = match symbol.kind {
+                SymbolKind::Category => (13, "category"),
+                _ => (20, "value"),
+            };
+            json!({ "label": symbol.name, "kind": kind, "detail": detail })
+        });
+        Value::Array(items.collect())
+    }
+
+    /// the sections, with their entries and the declared values within them
+    pub fn document_symbols(&self) -> Value {
+        Value::Array(self.sections.iter().map(document_symbol).collect())
+    }
+
+    fn symbols(&self) -> Vec<&Symbol> {
+        self.sections.iter().flat_map(Symbol::flatten).collect()
+    }
+
+    fn features(&self) -> Vec<&Symbol> {
+        let symbols = self.symbols().into_iter();
+        symbols
+            .filter(|symbol| matches!(symbol.kind, SymbolKind::Category | SymbolKind::Value))
+            .collect()
+    }
+
+    fn word_at(&self, line: usize, character: usize) -> Option<String> {
+        let chars = self.text.lines().nth(line)?.chars().collect::<Vec<_>>();
+        let is_word = |index: usize| chars.get(index).is_some_and(char::is_ascii_alphanumeric);
+        if !is_word(character) {
+            return None;
+        }
+        let start = (0..=character).rev().take_while(|&i| is_word(i)).last()?;
+        let end = (character..chars.len())
+            .take_while(|&i| is_word(i))
+            .last()?;
+        Some(chars[start..=end].iter().collect())
+    }
+
+    /// the word starting at a line and column from 1, for diagnostics that only have a start
+    fn word_range(&self, line: usize, column: usize) -> Value {
+        let chars = self.text.lines().nth(line - 1).unwrap_or_default().chars();
+        let word = chars
+            .skip(column - 1)
+            .take_while(char::is_ascii_alphanumeric);
+        range((line, column), (line, column + word.count()))
+    }
+}
+
+/// an LSP range, from lines and columns from 1 to lines and characters from 0
+fn range(start: (usize, usize), end: (usize, usize)) -> Value {
+    json!({
+        "start": { "line": start.0 - 1, "character": start.1 - 1 },
+        "end": { "line": end.0 - 1, "character": end.1 - 1 },
+    })
+}
+
+fn document_symbol(symbol: &Symbol) -> Value {
+    let kind = match symbol.kind {
+        SymbolKind::Section => 3,
+        SymbolKind::Category => 10,
+        SymbolKind::Value => 22,
+        SymbolKind::Suffix => 24,
+        SymbolKind::Functional => 12,
+        SymbolKind::Lexical => 15,
+        SymbolKind::Paradigm => 5,
+        SymbolKind::Guess => 15,
+    };
+    let range = range(symbol.start, symbol.end);
+    json!({
+        "name": symbol.name,
+        "kind": kind,
+        "range": range,
+        "selectionRange": range,
+        "children": symbol.children.iter().map(document_symbol).collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_features() {
+        let text = "[Features]
+person = local{1st, 2nd}, 3rd
+[Functional]
+DetP-local = (NounP-person > DetP)
+[Lexical]
+me = DetP-1st-3dr
+";
+        let document = Document::new(text.to_string());
+
+        let hover = document.hover(3).unwrap();
+        let contents = hover["contents"]["value"].as_str().unwrap();
+        assert_eq!(
+            contents,
+            "```\nDetP--person:?0--person=1st|2nd = (NounP--person:?0 > DetP)\n```"
+        );
+
+        // `local` in `DetP-local` is declared in `person = local{...}`
+        let definition = document.definition(3, 7).unwrap();
+        assert_eq!(definition["start"], json!({ "line": 1, "character": 9 }));
+
+        let completion = document.completion();
+        assert_eq!(completion.as_array().unwrap().len(), 5);
+
+        let diagnostics = document.diagnostics();
+        assert_eq!(diagnostics[0]["severity"], 2);
+        assert_eq!(
+            diagnostics[0]["range"],
+            json!({
+                "start": { "line": 5, "character": 14 },
+                "end": { "line": 5, "character": 17 },
+            })
+        );
+
+        let symbols = document.document_symbols();
+        assert_eq!(symbols[1]["name"], "[Functional]");
+        assert_eq!(symbols[1]["children"][0]["name"], "DetP-local");
+    }
+}
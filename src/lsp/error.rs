@@ -0,0 +1,14 @@
+use derive_more::{Display, From};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Display, From)]
+pub enum Error {
+    StdIO(std::io::Error),
+
+    /// a message without a `Content-Length` header
+    #[from(ignore)]
+    Protocol(String),
+}
+
+impl std::error::Error for Error {}
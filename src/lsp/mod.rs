@@ -0,0 +1,6 @@
+mod document;
+mod error;
+mod server;
+
+pub use error::Error;
+pub use server::serve;
@@ -0,0 +1,198 @@
+use super::document::Document;
+use super::error::{Error, Result};
+use log::debug;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+/// answers an editor over stdin and stdout until it exits
+pub fn serve() -> Result<()> {
+    serve_on(&mut std::io::stdin().lock(), &mut std::io::stdout().lock())
+}
+
+fn serve_on(reader: &mut impl BufRead, writer: &mut impl Write) -> Result<()> {
+    let mut documents: HashMap<String, Document> = HashMap::new();
+
+    while let Some(body) = read_message(reader)? {
+        // a message that is not JSON is answered as such, and the next one served
+        let message: Value = match serde_json::from_slice(&body) {
+            Ok(message) => message,
+            Err(error) => {
+                let error = json!({ "code": -32700, "message": format!("Parse error: {error}") });
+                let response = json!({ "jsonrpc": "2.0", "id": Value::Null, "error": error });
+                write_message(writer, &response)?;
+                continue;
+            }
+        };
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        debug!("lsp: {method}");
+        match message.get("id") {
+            _ if method == "exit" => break,
+            Some(id) => {
+                let response = match respond(&documents, method, params) {
+                    Some(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    None => {
+                        let error = json!({ "code": -32601, "message": format!("{method} is not supported") });
+                        json!({ "jsonrpc": "2.0", "id": id, "error": error })
+                    }
+                };
+                write_message(writer, &response)?;
+            }
+            None => {
+                if let Some(notification) = notify(&mut documents, method, params) {
+                    write_message(writer, &notification)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// the result of a request, if the method is supported
+fn respond(documents: &HashMap<String, Document>, method: &str, params: &Value) -> Option<Value> {
+    let document = params["textDocument"]["uri"]
+        .as_str()
+        .and_then(|uri| documents.get(uri));
+    let line = params["position"]["line"].as_u64().unwrap_or_default() as usize;
+    let character = params["position"]["character"].as_u64().unwrap_or_default() as usize;
+    let uri = &params["textDocument"]["uri"];
+
+    let result = match method {
+        "initialize" => json!({
+            "capabilities": {
+                "textDocumentSync": 1,
+                "hoverProvider": true,
+                "definitionProvider": true,
+                "completionProvider": {},
+                "documentSymbolProvider": true,
+            },
+            "serverInfo": { "name": "langbda" },
+        }),
+        "shutdown" => Value::Null,
+        "textDocument/hover" => document.and_then(|d| d.hover(line)).unwrap_or_default(),
+        "textDocument/definition" => match document.and_then(|d| d.definition(line, character)) {
+            Some(range) => json!({ "uri": uri, "range": range }),
+            None => Value::Null,
+        },
+        "textDocument/completion" => document.map(Document::completion).unwrap_or_default(),
+        "textDocument/documentSymbol" => {
+            document.map(Document::document_symbols).unwrap_or_default()
+        }
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// keeps the open documents in sync, returning the diagnostics to publish, if any
+fn notify(
+    documents: &mut HashMap<String, Document>,
+    method: &str,
+    params: &Value,
+) -> Option<Value> {
+    let uri = params["textDocument"]["uri"].as_str()?.to_string();
+    let diagnostics = match method {
+        "textDocument/didOpen" => {
+            let text = params["textDocument"]["text"].as_str()?.to_string();
+            let document = Document::new(text);
+            let diagnostics = document.diagnostics();
+            documents.insert(uri.clone(), document);
+            diagnostics
+        }
+        // the whole text is sent on every change, see `textDocumentSync`
+        "textDocument/didChange" => {
+            let text = params["contentChanges"].as_array()?.last()?["text"].as_str()?;
+            let document = documents.get_mut(&uri)?;
+            document.update(text.to_string());
+            document.diagnostics()
+        }
+        "textDocument/didClose" => {
+            documents.remove(&uri);
+            Value::Array(Vec::new())
+        }
+        _ => return None,
+    };
+    Some(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    }))
+}
+
+/// the body of the next message, or `None` once the editor has closed the stream
+fn read_message(reader: &mut impl BufRead) -> Result<Option<Vec<u8>>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            let value = value.trim().parse::<usize>();
+            length = Some(value.map_err(|_| Error::Protocol(format!("invalid header: {header}")))?);
+        }
+    }
+    let length = length.ok_or_else(|| Error::Protocol("no Content-Length header".to_string()))?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(Some(body))
+}
+
+fn write_message(writer: &mut impl Write, message: &Value) -> Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exchange_messages() {
+        let mut written = Vec::new();
+        let open = json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": "file:///a.lexicon", "text": "[Lexical]\na = (\n" } },
+        });
+        write_message(&mut written, &open).unwrap();
+        let body = read_message(&mut written.as_slice()).unwrap().unwrap();
+        let message: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(message, open);
+        assert!(read_message(&mut &b""[..]).unwrap().is_none());
+
+        let mut documents = HashMap::new();
+        let published = notify(&mut documents, "textDocument/didOpen", &message["params"]).unwrap();
+        let diagnostics = published["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["severity"], 1);
+
+        assert!(respond(&documents, "initialize", &Value::Null).is_some());
+        assert!(respond(&documents, "textDocument/rename", &Value::Null).is_none());
+    }
+
+    #[test]
+    fn malformed_message() {
+        let mut input = b"Content-Length: 1\r\n\r\n{".to_vec();
+        let shutdown = json!({ "jsonrpc": "2.0", "id": 1, "method": "shutdown" });
+        write_message(&mut input, &shutdown).unwrap();
+
+        // the malformed message is answered, and the server goes on to the next one
+        let mut output = Vec::new();
+        serve_on(&mut input.as_slice(), &mut output).unwrap();
+        let mut output = output.as_slice();
+        let error = read_message(&mut output).unwrap().unwrap();
+        let error: Value = serde_json::from_slice(&error).unwrap();
+        assert_eq!(error["error"]["code"], -32700);
+        assert_eq!(error["id"], Value::Null);
+        let response = read_message(&mut output).unwrap().unwrap();
+        let response: Value = serde_json::from_slice(&response).unwrap();
+        assert_eq!(response["id"], 1);
+    }
+}
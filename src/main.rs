@@ -5,50 +5,477 @@ mod interner;
 mod interpreter;
 mod lexicon;
 mod logger;
+mod lsp;
 mod syntax;
 mod tokenizer;
 mod trie;
 
-use self::cognitive::{LambdaModel, TreeModel};
-use self::dialect::{Dialect, English};
-use self::error::Result;
-use self::interpreter::{follow, interpret};
+use self::cognitive::{ChartModel, LambdaModel, RenderStyle, Snapshot, TreeModel};
+use self::dialect::{Dialect, English, FileDialect, Unknown};
+use self::error::{Error, Result};
+use self::interner::{GlobalKey, Interner, Scope};
+use self::interpreter::{
+    Action, compare, cross_validate, follow, follow_steps, interpret, interpret_forest,
+};
+use self::lexicon::SimpleLexicon;
+use self::lexicon::parser::{LexiconParser, PestLexiconParser};
 use self::logger::init_logger;
 
+const USAGE: &str = "\
+usage: langbda [options]                              render the example sentences
+       langbda [options] steps <sentence> [target]    render every step of every interpretation
+       langbda [options] check <sentence> [target]    replay every interpretation in both models
+       langbda [options] forest <sentence> [target]   count and render the interpretations as one forest
+       langbda lexicon validate [--strict] <file>     report errors and likely typos in a lexicon
+       langbda lexicon check <file> [target]          report entries that take part in no interpretation
+       langbda lexicon explain [--max <n>] <file>     show what each entry is loaded as and stands for
+       langbda lexicon fmt [--check] <file>           lay out a lexicon canonically, in place
+       langbda lexicon dump <file>                    write a lexicon back as loaded
+       langbda lexicon compile <file> <output>        write a lexicon as loaded to a binary file
+       langbda lexicon diff <old> <new> [corpus]      compare the entries, and the interpretations of a corpus
+       langbda lsp                                    serve .lexicon files to an editor over stdio
+
+options:
+       --style <file>    render with the style defined in <file>
+       --svg             render trees as SVG instead of PNG
+       --chart           search with branches sharing one chart instead of cloned stacks
+       --unknown <fail|suggest|guess>
+                         what to do with a word not in the lexicon, suggest by default
+       --lexicon <file>  interpret with the lexicon compiled to <file> instead of en.lexicon";
+
+struct Options {
+    /// parsed once the dialect is loaded, by its interner
+    style: Option<String>,
+    svg: bool,
+    chart: bool,
+    unknown: Unknown,
+    lexicon: Option<String>,
+}
+
 fn main() -> Result<()> {
     init_logger();
 
-    let dialect = English::init();
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let mut args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let mut options = Options {
+        style: None,
+        svg: false,
+        chart: false,
+        unknown: Unknown::default(),
+        lexicon: None,
+    };
+    loop {
+        match args.as_slice() {
+            ["--style", filename, ..] => {
+                options.style = Some(filename.to_string());
+                args.drain(..2);
+            }
+            ["--svg", ..] => {
+                options.svg = true;
+                args.drain(..1);
+            }
+            ["--chart", ..] => {
+                options.chart = true;
+                args.drain(..1);
+            }
+            ["--unknown", unknown, ..] => {
+                options.unknown = unknown.parse().map_err(Error::Usage)?;
+                args.drain(..2);
+            }
+            ["--lexicon", filename, ..] => {
+                options.lexicon = Some(filename.to_string());
+                args.drain(..2);
+            }
+            _ => break,
+        }
+    }
+
+    match args.as_slice() {
+        [] => render_examples(&options),
+        ["steps", sentence] => render_steps(sentence, "Sentence", &options),
+        ["steps", sentence, target] => render_steps(sentence, target, &options),
+        ["check", sentence] => check(sentence, "Sentence", &options),
+        ["check", sentence, target] => check(sentence, target, &options),
+        ["forest", sentence] => render_forest(sentence, "Sentence", &options),
+        ["forest", sentence, target] => render_forest(sentence, target, &options),
+        ["lexicon", "validate", filename] => validate_lexicon(filename, false),
+        ["lexicon", "validate", "--strict", filename] => validate_lexicon(filename, true),
+        ["lexicon", "explain", filename] => explain_lexicon(filename, 16),
+        ["lexicon", "explain", "--max", max, filename] => {
+            let max = max.parse().map_err(|_| Error::Usage(USAGE.to_string()))?;
+            explain_lexicon(filename, max)
+        }
+        ["lexicon", "fmt", filename] => format_lexicon(filename, false),
+        ["lexicon", "fmt", "--check", filename] => format_lexicon(filename, true),
+        ["lexicon", "dump", filename] => dump_lexicon(filename),
+        ["lexicon", "compile", filename, output] => compile_lexicon(filename, output),
+        ["lexicon", "diff", old, new] => diff_lexicons(old, new, None),
+        ["lexicon", "diff", old, new, corpus] => diff_lexicons(old, new, Some(corpus)),
+        ["lsp"] => Ok(lsp::serve()?),
+        ["lexicon", "check", filename] => check_lexicon(filename, "Sentence"),
+        ["lexicon", "check", filename, target] => check_lexicon(filename, target),
+        _ => Err(Error::Usage(USAGE.to_string())),
+    }
+}
+
+fn load_style(options: &Options) -> Result<RenderStyle<GlobalKey>> {
+    let Some(filename) = &options.style else {
+        return Ok(RenderStyle::default());
+    };
+    let input = std::fs::read_to_string(filename)?;
+    input.parse().map_err(Error::Parse)
+}
+
+fn english(options: &Options) -> Result<English> {
+    let mut dialect = match &options.lexicon {
+        Some(filename) => English::open(filename)?,
+        None => English::init(),
+    };
+    dialect.set_unknown(options.unknown);
+    Ok(dialect)
+}
+
+/// interpretations guessing the category of a word not in the lexicon are only as good as
+/// the guess
+fn report_guesses<K: Clone>(result: &[Vec<Action<K>>]) {
+    let guessed = result
+        .iter()
+        .filter(|actions| actions.iter().any(|action| action.is_guess()))
+        .count();
+    if guessed > 0 {
+        println!("{guessed} of them guess a word not in the lexicon.");
+    }
+}
+
+fn filename_sentence(sentence: &str) -> String {
+    sentence
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+fn render_examples(options: &Options) -> Result<()> {
+    let dialect = english(options)?;
+    let _scope = dialect.interner().enter();
+    let style = load_style(options)?;
     let name = dialect.name();
 
     let examples = vec![
         ("the child ate an apple in the room.", "Sentence"),
-        // ("the child did eat an apple.", "Sentence"), // still unsupported: see roadmap
-        // ("did the child eat an apple?", "Sentence"), // still unsupported: see roadmap
-        // ("whose apple did the child eat?", "Sentence"), // still unsupported: see roadmap
+        ("the child did eat an apple.", "Sentence"),
+        ("did the child eat an apple?", "Sentence"),
+        ("whose apple did the child eat?", "Sentence"),
     ];
 
     for (sentence, target) in examples {
         println!("Interpreting \"{sentence}\" as {target} in {name}");
-        let result = interpret::<_, LambdaModel<_>>(&dialect, sentence, target)?;
+        let result = match options.chart {
+            true => interpret::<_, ChartModel<_>>(&dialect, sentence, target)?,
+            false => interpret::<_, LambdaModel<_>>(&dialect, sentence, target)?,
+        };
 
         println!("LANGBDA found {} interpretations.", result.len());
-        let filename_sentence = sentence
-            .chars()
-            .map(|c| if c.is_alphanumeric() { c } else { '-' })
-            .collect::<String>();
+        report_guesses(&result);
+        let filename_sentence = filename_sentence(sentence);
         for (index, actions) in result.into_iter().enumerate() {
+            let guessed = actions.iter().any(|action| action.is_guess());
             let mut tree = follow::<_, TreeModel<_>>(target, actions)?;
             tree.prune().map_err(cognitive::Error::from)?;
 
+            let bracketed = tree.to_bracketed(&style);
+            let bracketed = bracketed.map_err(cognitive::Error::from)?;
+            match guessed {
+                true => println!("{bracketed} (guessed)"),
+                false => println!("{bracketed}"),
+            }
+
+            let filename = format!("assets/examples/{}_tree-{}", filename_sentence, index + 1);
+            let rendered = match options.svg {
+                true => tree.to_svg(format!("{filename}.svg"), &style),
+                false => tree.to_png(format!("{filename}.png"), &style),
+            };
+            rendered.map_err(cognitive::Error::from)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn render_steps(sentence: &str, target: &str, options: &Options) -> Result<()> {
+    let dialect = english(options)?;
+    let _scope = dialect.interner().enter();
+    let style = &load_style(options)?;
+    let name = dialect.name();
+
+    println!("Interpreting \"{sentence}\" as {target} in {name}");
+    let result = match options.chart {
+        true => interpret::<_, ChartModel<_>>(&dialect, sentence, target)?,
+        false => interpret::<_, LambdaModel<_>>(&dialect, sentence, target)?,
+    };
+
+    println!("LANGBDA found {} interpretations.", result.len());
+    report_guesses(&result);
+    let filename_sentence = filename_sentence(sentence);
+    for (index, actions) in result.into_iter().enumerate() {
+        let lambdas = follow_steps::<_, LambdaModel<_>>(target, actions.clone())?;
+        let trees = follow_steps::<_, TreeModel<_>>(target, actions)?;
+
+        for (step, (lambda, tree)) in lambdas.iter().zip(trees.iter()).enumerate() {
             let filename = format!(
-                "assets/examples/{}_tree-{}.png",
+                "assets/examples/{}_steps-{}-{}",
                 filename_sentence,
-                index + 1
+                index + 1,
+                step
             );
-            tree.to_png(filename).map_err(cognitive::Error::from)?;
+            lambda
+                .to_png(format!("{filename}_lambda.png"), style)
+                .map_err(cognitive::Error::from)?;
+            Snapshot::new(lambda, tree).to_png(format!("{filename}.png"), style)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn check(sentence: &str, target: &str, options: &Options) -> Result<()> {
+    let dialect = english(options)?;
+    let _scope = dialect.interner().enter();
+    let name = dialect.name();
+
+    println!("Interpreting \"{sentence}\" as {target} in {name}");
+    let result = match options.chart {
+        true => interpret::<_, ChartModel<_>>(&dialect, sentence, target)?,
+        false => interpret::<_, LambdaModel<_>>(&dialect, sentence, target)?,
+    };
+
+    println!("LANGBDA found {} interpretations.", result.len());
+    report_guesses(&result);
+    let mut diverged = 0;
+    for (index, actions) in result.into_iter().enumerate() {
+        let divergence = cross_validate::<_, LambdaModel<_>, TreeModel<_>>(target, actions)?;
+        if let Some(divergence) = divergence {
+            println!("Interpretation {}: {}", index + 1, divergence);
+            diverged += 1;
         }
     }
+    println!("{} interpretations diverged.", diverged);
+
+    Ok(())
+}
+
+fn render_forest(sentence: &str, target: &str, options: &Options) -> Result<()> {
+    let dialect = english(options)?;
+    let _scope = dialect.interner().enter();
+    let style = load_style(options)?;
+    let name = dialect.name();
 
+    println!("Interpreting \"{sentence}\" as {target} in {name}");
+    let forest = match options.chart {
+        true => interpret_forest::<_, ChartModel<_>>(&dialect, sentence, target)?,
+        false => interpret_forest::<_, LambdaModel<_>>(&dialect, sentence, target)?,
+    };
+
+    println!(
+        "LANGBDA found {} interpretations, sharing {} forest nodes.",
+        forest.count(),
+        forest.size()
+    );
+    let filename = format!("assets/examples/{}_forest.png", filename_sentence(sentence));
+    forest.to_png(filename, &style)?;
+
+    Ok(())
+}
+
+/// a fresh interner for the symbols of a lexicon loaded by a `lexicon` command, so that
+/// they are dropped with it
+fn lexicon_scope() -> Scope {
+    Interner::new().enter()
+}
+
+fn validate_lexicon(filename: &str, strict: bool) -> Result<()> {
+    let _scope = lexicon_scope();
+    let input = std::fs::read_to_string(filename)?;
+    let mut lexicon: SimpleLexicon<GlobalKey> = SimpleLexicon::new();
+    let result = match strict {
+        true => PestLexiconParser::parse_str_strict(&mut lexicon, &input),
+        false => PestLexiconParser::parse_str(&mut lexicon, &input),
+    };
+    let diagnostics = match &result {
+        Ok(diagnostics) | Err(diagnostics) => diagnostics,
+    };
+    for diagnostic in diagnostics {
+        println!("{filename}:{diagnostic}");
+    }
+    let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+    println!(
+        "{} errors, {} warnings.",
+        errors,
+        diagnostics.len() - errors
+    );
+    match result {
+        Ok(_) => Ok(()),
+        Err(_) => Err(Error::Parse(format!("{filename} has {errors} errors"))),
+    }
+}
+
+fn check_lexicon(filename: &str, target: &str) -> Result<()> {
+    let _scope = lexicon_scope();
+    let input = std::fs::read_to_string(filename)?;
+    let mut lexicon: SimpleLexicon<GlobalKey> = SimpleLexicon::new();
+    let result = PestLexiconParser::parse_str(&mut lexicon, &input);
+    if let Err(diagnostics) = result {
+        for diagnostic in diagnostics {
+            println!("{filename}:{diagnostic}");
+        }
+        return Err(Error::Parse(format!("{filename} cannot be loaded")));
+    }
+
+    let target = target.parse().map_err(|_| interpreter::Error::FromStr)?;
+    let findings = lexicon::check(&lexicon, target);
+    for finding in &findings {
+        println!("{finding}");
+    }
+    println!("{} findings.", findings.len());
+    Ok(())
+}
+
+fn format_lexicon(filename: &str, check: bool) -> Result<()> {
+    let input = std::fs::read_to_string(filename)?;
+    let formatted = PestLexiconParser::format(&input)
+        .map_err(|diagnostic| Error::Parse(format!("{filename}:{diagnostic}")))?;
+    match (check, formatted == input) {
+        (_, true) => Ok(()),
+        (true, false) => Err(Error::Parse(format!("{filename} is not formatted"))),
+        (false, false) => Ok(std::fs::write(filename, formatted)?),
+    }
+}
+
+fn dump_lexicon(filename: &str) -> Result<()> {
+    let _scope = lexicon_scope();
+    let input = std::fs::read_to_string(filename)?;
+    let mut lexicon: SimpleLexicon<GlobalKey> = SimpleLexicon::new();
+    if let Err(diagnostics) = PestLexiconParser::parse_str(&mut lexicon, &input) {
+        for diagnostic in diagnostics {
+            println!("{filename}:{diagnostic}");
+        }
+        return Err(Error::Parse(format!("{filename} cannot be loaded")));
+    }
+    print!("{}", lexicon::serialize(&lexicon));
+    Ok(())
+}
+
+fn compile_lexicon(filename: &str, output: &str) -> Result<()> {
+    let _scope = lexicon_scope();
+    let input = std::fs::read_to_string(filename)?;
+    let mut lexicon: SimpleLexicon<GlobalKey> = SimpleLexicon::new();
+    if let Err(diagnostics) = PestLexiconParser::parse_str(&mut lexicon, &input) {
+        for diagnostic in diagnostics {
+            println!("{filename}:{diagnostic}");
+        }
+        return Err(Error::Parse(format!("{filename} cannot be loaded")));
+    }
+    let compiled = lexicon::compile(&lexicon);
+    std::fs::write(output, &compiled)?;
+    println!(
+        "{output}: {} bytes, version {}.",
+        compiled.len(),
+        lexicon::VERSION
+    );
+    Ok(())
+}
+
+fn explain_lexicon(filename: &str, max: usize) -> Result<()> {
+    let _scope = lexicon_scope();
+    let input = std::fs::read_to_string(filename)?;
+    let explanations = match lexicon::explain::<GlobalKey>(&input, max) {
+        Ok(explanations) => explanations,
+        Err(diagnostics) => {
+            for diagnostic in diagnostics {
+                println!("{filename}:{diagnostic}");
+            }
+            return Err(Error::Parse(format!("{filename} cannot be loaded")));
+        }
+    };
+    for explanation in explanations {
+        println!("{}: {}", explanation.line, explanation.source);
+        for loaded in &explanation.loaded {
+            println!("    loaded as {loaded}");
+        }
+        if explanation.size > max {
+            println!(
+                "    warning: stands for {} entries, more than {max}, see --max",
+                explanation.size
+            );
+        } else if explanation.size > 1 {
+            println!("    stands for {} entries:", explanation.size);
+            for (bindings, entry) in &explanation.expansion {
+                println!("        {bindings}: {entry}");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn load_dialect(filename: &str) -> Result<FileDialect> {
+    let input = std::fs::read_to_string(filename)?;
+    FileDialect::load(filename, &input).map_err(|diagnostics| {
+        for diagnostic in diagnostics {
+            println!("{filename}:{diagnostic}");
+        }
+        Error::Parse(format!("{filename} cannot be loaded"))
+    })
+}
+
+fn diff_lexicons(old: &str, new: &str, corpus: Option<&str>) -> Result<()> {
+    let _scope = lexicon_scope();
+    let before = std::fs::read_to_string(old)?;
+    let after = std::fs::read_to_string(new)?;
+    let (added, removed) = lexicon::diff::<GlobalKey>(&before, &after).map_err(|diagnostics| {
+        for diagnostic in diagnostics {
+            println!("{diagnostic}");
+        }
+        Error::Parse(format!("{old} or {new} cannot be loaded"))
+    })?;
+    for entry in &removed {
+        println!("- {entry}");
+    }
+    for entry in &added {
+        println!("+ {entry}");
+    }
+    println!("{} entries removed, {} added.", removed.len(), added.len());
+
+    let Some(corpus) = corpus else {
+        return Ok(());
+    };
+    let (before, after) = (load_dialect(old)?, load_dialect(new)?);
+    let corpus = std::fs::read_to_string(corpus)?;
+    let mut changed = 0;
+    // a sentence per line, optionally followed by a tab and its target
+    for line in corpus.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (sentence, target) = line.split_once('\t').unwrap_or((line, "Sentence"));
+        let comparison =
+            compare::<_, LambdaModel<_>>(&before, &after, sentence.trim(), target.trim())?;
+        if comparison.is_same() {
+            continue;
+        }
+        changed += 1;
+        println!(
+            "\"{sentence}\": {} interpretations, then {}",
+            comparison.before, comparison.after
+        );
+        for (sign, interpretations) in [("-", &comparison.lost), ("+", &comparison.gained)] {
+            for actions in interpretations {
+                println!("  {sign}");
+                for action in actions {
+                    println!("    {action}");
+                }
+            }
+        }
+    }
+    println!("{changed} sentences interpreted differently.");
     Ok(())
 }
@@ -0,0 +1,147 @@
+use super::constraint::Constraint;
+use std::collections::BTreeMap;
+
+/// needs: Copy
+pub type Variable = usize;
+
+/// what a variable stands for once unified
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Binding<K> {
+    Value(Option<K>),
+    Variable(Variable),
+}
+
+/// variables bound by unification, to be substituted wherever they occur
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bindings<K> {
+    map: BTreeMap<Variable, Binding<K>>,
+    /// what the variables not bound yet may stand for
+    constraints: BTreeMap<Variable, Constraint<K>>,
+}
+
+impl<K> Default for Bindings<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> Bindings<K> {
+    pub fn new() -> Self {
+        Bindings {
+            map: BTreeMap::new(),
+            constraints: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty() && self.constraints.is_empty()
+    }
+
+    /// the constraint on a variable that is not bound, see `resolve`
+    pub fn get_constraint(&self, variable: Variable) -> Option<&Constraint<K>> {
+        self.constraints.get(&variable)
+    }
+}
+
+impl<K: Clone + Ord> Bindings<K> {
+    /// follows `variable` through the variables it is bound to
+    pub fn resolve(&self, mut variable: Variable) -> Binding<K> {
+        loop {
+            match self.map.get(&variable) {
+                Some(Binding::Variable(next)) => variable = *next,
+                Some(Binding::Value(value)) => return Binding::Value(value.clone()),
+                None => return Binding::Variable(variable),
+            }
+        }
+    }
+
+    /// returns whether `variable` can stand for `value`
+    pub fn bind_value(&mut self, variable: Variable, value: &Option<K>) -> bool {
+        match self.resolve(variable) {
+            Binding::Variable(free) => {
+                let allowed = self.constraints.get(&free);
+                if !allowed.is_none_or(|constraint| constraint.allows(value)) {
+                    return false;
+                }
+                self.constraints.remove(&free);
+                self.map.insert(free, Binding::Value(value.clone()));
+                true
+            }
+            Binding::Value(bound) => &bound == value,
+        }
+    }
+
+    /// returns whether `variable` can stand for a value allowed by `constraint`
+    pub fn constrain(&mut self, variable: Variable, constraint: Option<&Constraint<K>>) -> bool {
+        let Some(constraint) = constraint else {
+            return true;
+        };
+        match self.resolve(variable) {
+            // a variable stands for a value, so it is never absent
+            Binding::Variable(_) if constraint == &Constraint::Absent => false,
+            Binding::Variable(free) => {
+                let merged = match self.constraints.get(&free) {
+                    Some(existing) => existing.merge(constraint),
+                    None => Some(constraint.clone()),
+                };
+                match merged {
+                    Some(merged) => {
+                        self.constraints.insert(free, merged);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            Binding::Value(value) => constraint.allows(&value),
+        }
+    }
+
+    /// returns whether `a` and `b` can stand for the same value
+    pub fn bind_variables(&mut self, a: Variable, b: Variable) -> bool {
+        match (self.resolve(a), self.resolve(b)) {
+            (Binding::Variable(a), Binding::Variable(b)) => {
+                if a == b {
+                    return true;
+                }
+                self.map.insert(a, Binding::Variable(b));
+                let constraint = self.constraints.remove(&a);
+                self.constrain(b, constraint.as_ref())
+            }
+            (Binding::Variable(free), Binding::Value(value))
+            | (Binding::Value(value), Binding::Variable(free)) => self.bind_value(free, &value),
+            (Binding::Value(a), Binding::Value(b)) => a == b,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn bind_through_variables() {
+        let mut bindings = Bindings::new();
+        assert!(bindings.bind_variables(0, 1));
+        assert!(bindings.bind_variables(1, 2));
+        assert!(bindings.bind_value(0, &Some("sg")));
+        assert_eq!(bindings.resolve(2), Binding::Value(Some("sg")));
+        assert!(!bindings.bind_value(1, &Some("pl")));
+        assert!(bindings.bind_value(1, &Some("sg")));
+    }
+
+    #[test]
+    fn bind_constrained_variables() {
+        let mut bindings = Bindings::new();
+        let local = Constraint::OneOf(BTreeSet::from(["1st", "2nd"]));
+        let not_first = Constraint::NoneOf(BTreeSet::from(["1st"]));
+        assert!(bindings.constrain(0, Some(&local)));
+        assert!(bindings.constrain(1, Some(&not_first)));
+        assert!(bindings.bind_variables(0, 1));
+        let second = Constraint::OneOf(BTreeSet::from(["2nd"]));
+        assert_eq!(bindings.get_constraint(1), Some(&second));
+        assert!(!bindings.clone().bind_value(0, &Some("1st")));
+        assert!(bindings.bind_value(0, &Some("2nd")));
+        assert!(!bindings.constrain(2, Some(&Constraint::Absent)));
+    }
+}
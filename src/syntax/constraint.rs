@@ -0,0 +1,83 @@
+use std::collections::BTreeSet;
+use std::fmt::Display;
+
+/// what a category may be, when its value is not known yet
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Constraint<K> {
+    /// `person=1st|2nd`
+    OneOf(BTreeSet<K>),
+    /// `case!=obj`
+    NoneOf(BTreeSet<K>),
+    /// `!case`, the category must not be there at all
+    Absent,
+}
+
+impl<K: Clone + Ord> Constraint<K> {
+    pub fn allows(&self, value: &Option<K>) -> bool {
+        match (self, value) {
+            (Constraint::OneOf(values), Some(value)) => values.contains(value),
+            (Constraint::OneOf(_), None) => false,
+            (Constraint::NoneOf(values), Some(value)) => !values.contains(value),
+            (Constraint::NoneOf(_), None) => true,
+            (Constraint::Absent, _) => false,
+        }
+    }
+
+    /// the constraint allowing what both allow, if anything
+    pub fn merge(&self, other: &Constraint<K>) -> Option<Constraint<K>> {
+        let merged = match (self, other) {
+            (Constraint::Absent, Constraint::Absent) => Constraint::Absent,
+            (Constraint::Absent, _) | (_, Constraint::Absent) => return None,
+            (Constraint::OneOf(a), Constraint::OneOf(b)) => {
+                Constraint::OneOf(a.intersection(b).cloned().collect())
+            }
+            (Constraint::OneOf(a), Constraint::NoneOf(b))
+            | (Constraint::NoneOf(b), Constraint::OneOf(a)) => {
+                Constraint::OneOf(a.difference(b).cloned().collect())
+            }
+            (Constraint::NoneOf(a), Constraint::NoneOf(b)) => {
+                Constraint::NoneOf(a.union(b).cloned().collect())
+            }
+        };
+        match &merged {
+            Constraint::OneOf(values) if values.is_empty() => None,
+            _ => Some(merged),
+        }
+    }
+}
+
+impl<K: Display> Display for Constraint<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |values: &BTreeSet<K>| {
+            let values = values.iter().map(|value| format!("{value}"));
+            values.collect::<Vec<_>>().join("|")
+        };
+        match self {
+            Constraint::OneOf(values) => write!(f, "={}", join(values)),
+            Constraint::NoneOf(values) => write!(f, "!={}", join(values)),
+            Constraint::Absent => write!(f, "!"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_constraints() {
+        let one_of = Constraint::OneOf(BTreeSet::from(["1st", "2nd"]));
+        let none_of = Constraint::NoneOf(BTreeSet::from(["1st"]));
+        let merged = one_of.merge(&none_of).unwrap();
+        assert_eq!(merged, Constraint::OneOf(BTreeSet::from(["2nd"])));
+        assert!(merged.allows(&Some("2nd")));
+        assert!(!merged.allows(&Some("1st")));
+        assert!(merged.merge(&none_of.merge(&none_of).unwrap()).is_some());
+        assert!(
+            merged
+                .merge(&Constraint::NoneOf(BTreeSet::from(["2nd"])))
+                .is_none()
+        );
+        assert!(one_of.merge(&Constraint::Absent).is_none());
+    }
+}
@@ -5,6 +5,7 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug, Display)]
 pub enum Error {
     CategoryAlreadyHasValue,
+    CategoryMustBeAbsent,
     TypeConversion,
 }
 
@@ -1,3 +1,5 @@
+use super::bindings::{Binding, Bindings, Variable};
+use super::constraint::Constraint;
 use super::error::{Error, Result};
 use std::collections::BTreeMap;
 use std::fmt::Debug;
@@ -5,6 +7,10 @@ use std::fmt::Debug;
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct FeatureSet<K> {
     map: BTreeMap<K, Option<K>>,
+    /// categories whose value is not known yet, shared with other feature sets by variable
+    variables: BTreeMap<K, Variable>,
+    /// what the variables may stand for, and the categories that must be absent
+    constraints: BTreeMap<K, Constraint<K>>,
 }
 
 impl<K> Default for FeatureSet<K> {
@@ -17,6 +23,8 @@ impl<K> FeatureSet<K> {
     pub fn new() -> Self {
         FeatureSet {
             map: BTreeMap::new(),
+            variables: BTreeMap::new(),
+            constraints: BTreeMap::new(),
         }
     }
 }
@@ -28,6 +36,8 @@ where
     pub fn from_category(category: K) -> Self {
         FeatureSet {
             map: BTreeMap::from([(category, None)]),
+            variables: BTreeMap::new(),
+            constraints: BTreeMap::new(),
         }
     }
 }
@@ -41,23 +51,89 @@ where
     }
 
     pub fn insert(&mut self, key: K, value: Option<K>) {
+        self.variables.remove(&key);
+        self.constraints.remove(&key);
         self.map.insert(key, value);
     }
 
     pub fn remove(&mut self, key: &K) {
         self.map.remove(key);
+        self.variables.remove(key);
+        self.constraints.remove(key);
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.map.contains_key(key) || self.variables.contains_key(key)
+    }
+
+    /// every category with a value or a variable
+    pub fn categories(&self) -> impl Iterator<Item = &K> {
+        self.map.keys().chain(self.variables.keys())
+    }
+
+    pub fn get_variable(&self, key: &K) -> Option<Variable> {
+        self.variables.get(key).copied()
+    }
+
+    pub fn insert_variable(&mut self, key: K, variable: Variable) {
+        self.map.remove(&key);
+        if self.is_absent(&key) {
+            self.constraints.remove(&key);
+        }
+        self.variables.insert(key, variable);
+    }
+
+    pub fn get_constraint(&self, key: &K) -> Option<&Constraint<K>> {
+        self.constraints.get(key)
+    }
+
+    /// constrains the variable of `key`, or requires `key` to be absent
+    pub fn insert_constraint(&mut self, key: K, constraint: Constraint<K>) {
+        if constraint == Constraint::Absent {
+            self.map.remove(&key);
+            self.variables.remove(&key);
+        }
+        self.constraints.insert(key, constraint);
+    }
+
+    /// every category required to be absent, e.g., `case` in `!case`
+    pub fn absent(&self) -> impl Iterator<Item = &K> {
+        let constraints = self.constraints.iter();
+        constraints
+            .filter(|(_, constraint)| **constraint == Constraint::Absent)
+            .map(|(category, _)| category)
+    }
+
+    fn is_absent(&self, key: &K) -> bool {
+        self.constraints.get(key) == Some(&Constraint::Absent)
     }
 
     pub fn insert_if_absent(&mut self, key: K, value: Option<K>) -> Result<()> {
-        match self.get(&key) {
-            Some(_) => Err(Error::CategoryAlreadyHasValue),
-            None => {
+        if self.is_absent(&key) {
+            return Err(Error::CategoryMustBeAbsent);
+        }
+        match self.contains_key(&key) {
+            true => Err(Error::CategoryAlreadyHasValue),
+            false => {
                 self.insert(key, value);
                 Ok(())
             }
         }
     }
 
+    fn insert_variable_if_absent(&mut self, key: K, variable: Variable) -> Result<()> {
+        if self.is_absent(&key) {
+            return Err(Error::CategoryMustBeAbsent);
+        }
+        match self.contains_key(&key) {
+            true => Err(Error::CategoryAlreadyHasValue),
+            false => {
+                self.insert_variable(key, variable);
+                Ok(())
+            }
+        }
+    }
+
     pub fn contains_key_value(&self, key: &K, value: &Option<K>) -> bool {
         match self.get(key) {
             Some(v) => v == value,
@@ -76,26 +152,129 @@ where
         onto: &mut FeatureSet<K>,
         ignore: &FeatureSet<K>,
     ) -> Result<()> {
+        // agreeing with what is already there is not a conflict
         for (category, value) in from.map.iter() {
-            if !ignore.contains_key_value(category, value) {
+            if !ignore.contains_key_value(category, value)
+                && !onto.contains_key_value(category, value)
+            {
                 onto.insert_if_absent(category.clone(), value.clone())?;
             }
         }
+        for (category, &variable) in from.variables.iter() {
+            if ignore.get_variable(category) != Some(variable)
+                && onto.get_variable(category) != Some(variable)
+            {
+                onto.insert_variable_if_absent(category.clone(), variable)?;
+                if let Some(constraint) = from.get_constraint(category) {
+                    onto.insert_constraint(category.clone(), constraint.clone());
+                }
+            }
+        }
         Ok(())
     }
 
+    /// exact comparison, a variable only matches itself
     pub fn is_subset(&self, other: &FeatureSet<K>) -> bool {
         for (category, value) in self.map.iter() {
             if !other.contains_key_value(category, value) {
                 return false;
             }
         }
+        for (category, variable) in self.variables.iter() {
+            if other.variables.get(category) != Some(variable) {
+                return false;
+            }
+        }
+        for (category, constraint) in self.constraints.iter() {
+            if other.constraints.get(category) != Some(constraint) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// like `is_subset`, binding the variables on either side so that it holds
+    pub fn unify(&self, other: &FeatureSet<K>, bindings: &mut Bindings<K>) -> bool {
+        for (category, value) in self.map.iter() {
+            let unified = match (other.map.get(category), other.get_variable(category)) {
+                (Some(other), _) => other == value,
+                (None, Some(variable)) => {
+                    bindings.constrain(variable, other.get_constraint(category))
+                        && bindings.bind_value(variable, value)
+                }
+                (None, None) => false,
+            };
+            if !unified {
+                return false;
+            }
+        }
+        for (category, &variable) in self.variables.iter() {
+            if !bindings.constrain(variable, self.get_constraint(category)) {
+                return false;
+            }
+            let unified = match (other.map.get(category), other.get_variable(category)) {
+                (Some(value), _) => bindings.bind_value(variable, value),
+                (None, Some(other_variable)) => {
+                    bindings.constrain(other_variable, other.get_constraint(category))
+                        && bindings.bind_variables(variable, other_variable)
+                }
+                (None, None) => false,
+            };
+            if !unified {
+                return false;
+            }
+        }
+        // `!case` holds as long as there is no case
+        for category in self.constraints.keys() {
+            if self.is_absent(category) && other.contains_key(category) {
+                return false;
+            }
+        }
         true
     }
+
+    /// replaces bound variables by what they are bound to
+    pub fn substitute(&mut self, bindings: &Bindings<K>) {
+        if bindings.is_empty() {
+            return;
+        }
+        let variables = std::mem::take(&mut self.variables);
+        for (category, variable) in variables {
+            match bindings.resolve(variable) {
+                Binding::Value(value) => {
+                    self.constraints.remove(&category);
+                    self.map.insert(category, value);
+                }
+                Binding::Variable(variable) => {
+                    if let Some(constraint) = bindings.get_constraint(variable) {
+                        self.constraints
+                            .insert(category.clone(), constraint.clone());
+                    }
+                    self.variables.insert(category, variable);
+                }
+            }
+        }
+    }
+
+    /// renames every variable `v` to `v + offset`, so that entries do not share variables
+    pub fn offset_variables(&mut self, offset: Variable) {
+        for variable in self.variables.values_mut() {
+            *variable += offset;
+        }
+    }
+
+    /// the smallest variable above every variable in this set
+    pub fn next_variable(&self) -> Variable {
+        self.variables.values().map(|v| v + 1).max().unwrap_or(0)
+    }
+
+    pub fn variables(&self) -> impl Iterator<Item = Variable> + '_ {
+        self.variables.values().copied()
+    }
 }
 
 impl<K> FeatureSet<K> {
-    pub fn iter(&self) -> FeatureSetIter<K> {
+    pub fn iter(&self) -> FeatureSetIter<'_, K> {
         FeatureSetIter {
             inner: self.map.iter(),
         }
@@ -134,6 +313,8 @@ where
     fn from_iter<I: IntoIterator<Item = (K, Option<K>)>>(iter: I) -> Self {
         FeatureSet {
             map: BTreeMap::from_iter(iter),
+            variables: BTreeMap::new(),
+            constraints: BTreeMap::new(),
         }
     }
 }
@@ -152,6 +333,15 @@ where
                 None => entries.push(format!("{category}")),
             }
         }
+        for (category, variable) in self.variables.iter() {
+            entries.push(format!("{category}:?{variable}"));
+        }
+        for (category, constraint) in self.constraints.iter() {
+            match constraint {
+                Constraint::Absent => entries.push(format!("!{category}")),
+                constraint => entries.push(format!("{category}{constraint}")),
+            }
+        }
 
         entries.sort();
         write!(f, "{}", entries.join("--"))
@@ -201,4 +391,59 @@ mod tests {
         feature_set.insert(c, Some(d));
         assert_eq!(format!("{feature_set}"), "a:b--c:d");
     }
+
+    #[test]
+    fn unify_feature_set() {
+        // DetH-number:?0 looked up by DetH-number:sg, expecting NounP-number:?0
+        let mut key = FeatureSet::from_category("DetH");
+        key.insert_variable("number", 0);
+        let mut in_hand = FeatureSet::from_category("DetH");
+        in_hand.insert("number", Some("sg"));
+        let mut bindings = Bindings::new();
+        assert!(key.unify(&in_hand, &mut bindings));
+
+        let mut expected = FeatureSet::from_category("NounP");
+        expected.insert_variable("number", 0);
+        expected.substitute(&bindings);
+        let mut noun = FeatureSet::from_category("NounP");
+        noun.insert("number", Some("sg"));
+        assert_eq!(expected, noun);
+
+        noun.insert("number", Some("pl"));
+        assert!(!expected.unify(&noun, &mut Bindings::new()));
+        assert!(!key.unify(&FeatureSet::from_category("DetH"), &mut Bindings::new()));
+    }
+
+    #[test]
+    fn unify_constraints() {
+        use std::collections::BTreeSet;
+        // DetP-case!=obj-!person
+        let mut pattern = FeatureSet::from_category("DetP");
+        pattern.insert_variable("case", 0);
+        pattern.insert_constraint("case", Constraint::NoneOf(BTreeSet::from(["obj"])));
+        pattern.insert_constraint("person", Constraint::Absent);
+
+        let mut subj = FeatureSet::from_category("DetP");
+        subj.insert("case", Some("subj"));
+        assert!(pattern.unify(&subj, &mut Bindings::new()));
+        let mut obj = subj.clone();
+        obj.insert("case", Some("obj"));
+        assert!(!pattern.unify(&obj, &mut Bindings::new()));
+        subj.insert("person", Some("3rd"));
+        assert!(!pattern.unify(&subj, &mut Bindings::new()));
+
+        // the constraint carries over to a variable in hand
+        let mut unknown = FeatureSet::from_category("DetP");
+        unknown.insert_variable("case", 1);
+        let mut bindings = Bindings::new();
+        assert!(pattern.unify(&unknown, &mut bindings));
+        unknown.substitute(&bindings);
+        assert_eq!(format!("{unknown}"), "DetP--case!=obj--case:?1");
+        assert!(!unknown.unify(&obj, &mut Bindings::new()));
+
+        // nothing may be projected onto an absent category
+        let mut onto = FeatureSet::from_category("TenseP");
+        onto.insert_constraint("person", Constraint::Absent);
+        assert!(FeatureSet::project(&subj, &mut onto, &FeatureSet::new()).is_err());
+    }
 }
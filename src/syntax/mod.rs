@@ -1,7 +1,11 @@
+mod bindings;
+mod constraint;
 mod error;
 mod feature_set;
 mod syntax_value;
 
+pub use bindings::{Bindings, Variable};
+pub use constraint::Constraint;
 pub use error::Error;
 pub use feature_set::FeatureSet;
 pub use syntax_value::SyntaxValue;
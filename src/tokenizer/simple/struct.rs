@@ -6,7 +6,7 @@ pub struct SimpleTokenizer;
 
 impl<K> Tokenizer<K> for SimpleTokenizer
 where
-    K: FromStr + Copy,
+    K: FromStr,
 {
     fn tokenize<'a>(&self, input: &'a str) -> Vec<(K, &'a str)> {
         let input = input.trim_start();